@@ -1,28 +1,37 @@
+use super::places::{autocomplete_place, button_row, find_places, reselect_place, reselection_button, saved_place,
+                    select_place, Selection, SentResponse, HOME_PLACE, RESELECTION_ID};
+use super::time::{describe_localization, describe_timezone_source, place_timezone};
 use crate::localization::*;
 use crate::sources::common::*;
 use crate::sources::{climate_forecast as forecast, climate_historical as historic, geo_time, nominatim};
+use geo_time::{LocalizedTime, ResolvedTimezone};
 use crate::utils::parsing;
 use crate::{Context, Error};
-use nominatim::Place;
+use nominatim::{FeatureKind, Place};
 
-use chrono::{NaiveDateTime, TimeZone};
+use chrono::{DateTime, NaiveDateTime};
 use chrono_tz::Tz;
-use poise::{serenity_prelude as serenity, CreateReply};
-use serenity::{CreateSelectMenuKind, CreateSelectMenuOption as MenuOption, Mention};
+use fluent_templates::LanguageIdentifier;
+use poise::serenity_prelude::{self as serenity, CreateMessage, Mention};
+use poise::CreateReply;
+
+const OTHER_TIME_ID: &str = "temperature_other_time";
 
 #[poise::command(slash_command, prefix_command, track_edits, aliases("temp"))]
 pub async fn temperature(ctx: Context<'_>,
-                         #[description = "Name of a place"] place: String,
+                         #[description = "Name of a place, coordinates, a Plus Code or one of your saved places"]
+                         #[autocomplete = "autocomplete_place"] place: Option<String>,
                          #[description = "A specific date in the past"] date: Option<String>,
-                         #[description = "A specific time of day"] time: Option<String>
+                         #[description = "A specific time of day"] time: Option<String>,
+                         #[description = "Kind of place to search for"] kind: Option<FeatureKind>,
+                         #[description = "Country name or ISO code to search the place in"] country: Option<String>,
 ) -> Result<(), Error> {
     // firstly, check if the user-provided timestamp can be parsed (if applicable)
     let timestamp = if date.is_some() || time.is_some() {
         let parsed_datetime = match parsing::parse_datetime(date, time) {
             Ok(timestamp) => timestamp,
             Err(e) => {
-                // todo!("Output user feedback to the discord context regarding timestamp parsing error");
-                //ctx.reply(localize!("timestamp-parse-error")).await?;
+                ctx.reply(localize!(ctx, "timestamp-parse-error", error: &e)).await?;
                 return Err(Error::Handled {inner: Box::new(e)});
             }
         };
@@ -31,78 +40,141 @@ pub async fn temperature(ctx: Context<'_>,
         None
     };
 
-    // look up the requested place
-    let places = nominatim::query_place(&ctx.data().http_client, &place).await?;
+    // default to the user's home if no place is given
+    let place = match place {
+        Some(place) => place,
+        None if saved_place(ctx, HOME_PLACE).is_some() => HOME_PLACE.to_string(),
+        None => {
+            ctx.reply(localize!(ctx, "place-missing-no-home", home: HOME_PLACE)).await?;
+            return Ok(());
+        }
+    };
+    let places = find_places(ctx, &place, kind.unwrap_or_default(), country.as_deref()).await?;
 
     if places.is_empty() {
-        ctx.reply(localize!("place-not-found", search_term: &place)).await?;
+        ctx.reply(localize!(ctx, "place-not-found", search_term: &place)).await?;
         return Ok(());
     }
 
     // select a place from the list
-    match select_place(ctx, &places).await {
-        Selection::Unique(place) => {
-            let data = get_single_temperature(&ctx.data().http_client, place, timestamp).await?;
-            let response = create_temperature_response(place, data);
-            ctx.reply(response).await?;
-        },
-        Selection::OneOfMany(place) => {
-            let data = get_single_temperature(&ctx.data().http_client, place, timestamp).await?;
-            let mut response = create_temperature_response(place, data);
+    let language = query_language(ctx);
+    let client = &ctx.data().http_client;
+    let search_term = place;
+    let mut selection = select_place(ctx, &places, &search_term).await;
+
+    loop {
+        // a place picked from a selection menu is not a reply, since the menu already was one
+        let (place, remembered, is_reply) = match selection {
+            Selection::Unique(place) => (place, false, true),
+            Selection::Remembered(place) => (place, true, true),
+            Selection::OneOfMany(place) => (place, false, false),
+            Selection::Aborted => {
+                ctx.channel_id().say(ctx.http(), localize!(ctx, "place-selection-timeout")).await?;
+                return Ok(());
+            },
+            Selection::Failed(error) => return Err(error),
+        };
+
+        let timezone = place_timezone(place);
+        let localized = timestamp.map(|timestamp| localize_timestamp(timezone.as_ref(), &timestamp));
+        let data = get_single_temperature(client, place, localized.as_ref().map(LocalizedTime::instant)).await?;
+        let mut response = create_temperature_response(ctx, place, data, &language);
+        // the timezone only matters for a requested time, the current temperature is independent of it
+        let notes = [
+            timezone.as_ref().filter(|_| localized.is_some()).and_then(|resolved| describe_timezone_source(ctx, resolved)),
+            localized.as_ref().and_then(|localized| describe_localization(ctx, localized)),
+        ];
+        for note in notes.into_iter().flatten() {
+            response = format!("{}\n{}", response, note);
+        }
+
+        let mut buttons = Vec::new();
+        // the user may still decide that they meant a different place this time
+        if remembered {
+            buttons.push(reselection_button(ctx));
+        }
+        if let Some(LocalizedTime::Ambiguous { later, .. }) = localized {
+            buttons.push(other_time_button(ctx, &later));
+        }
 
+        let mut sent = if is_reply {
+            SentResponse::Reply(ctx.send(CreateReply::default()
+                .content(response)
+                .components(button_row(buttons.clone()))
+                .reply(true)
+            ).await?)
+        } else {
             // Since this response will not be formatted as a reply to a slash command,
             // mention the user who invoked this command
-            response = localize!("response-invoked-by",
+            let response = localize!(ctx, "response-invoked-by",
                 message: response,
                 user_mention: Mention::User(ctx.author().id)
             );
+            let message = CreateMessage::new().content(response).components(button_row(buttons.clone()));
+            SentResponse::Message(Box::new(ctx.channel_id().send_message(ctx.http(), message).await?))
+        };
+
+        if buttons.is_empty() {
+            return Ok(());
+        }
 
-            ctx.channel_id().say(ctx.http(), response).await?;
-        },
-        Selection::Aborted => {
-            ctx.channel_id().say(ctx.http(), localize!("place-selection-timeout")).await?;
-        },
-        Selection::Failed(error) => {
-            return Err(error)
-        },
+        match sent.await_button(ctx).await?.as_deref() {
+            Some(RESELECTION_ID) => selection = reselect_place(ctx, &places, &search_term).await,
+            Some(OTHER_TIME_ID) => {
+                let Some(LocalizedTime::Ambiguous { later, .. }) = localized else {
+                    return Ok(());
+                };
+                let data = get_single_temperature(client, place, Some(&later)).await?;
+                let note = localize!(ctx, "dst-ambiguous-other",
+                    time: later.format(&localize_raw!(ctx, "format-time")),
+                    abbreviation: later.format("%Z")
+                );
+                let response = localize!(ctx, "response-invoked-by",
+                    message: format!("{}\n{}", create_temperature_response(ctx, place, data, &language), note),
+                    user_mention: Mention::User(ctx.author().id)
+                );
+                ctx.channel_id().say(ctx.http(), response).await?;
+                return Ok(());
+            },
+            _ => return Ok(()),
+        }
     }
+}
 
-    Ok(())
+fn other_time_button(ctx: Context<'_>, later: &DateTime<Tz>) -> serenity::CreateButton {
+    serenity::CreateButton::new(OTHER_TIME_ID)
+        .label(localize!(ctx, "dst-show-other",
+            time: later.format(&localize_raw!(ctx, "format-time")),
+            abbreviation: later.format("%Z")
+        ))
+        .style(serenity::ButtonStyle::Secondary)
 }
 
-pub enum Selection<T> {
-    Unique(T),
-    OneOfMany(T),
-    Aborted,
-    Failed(Error),
+// include place-local timezone information in the requested datetime
+fn localize_timestamp(timezone: Option<&ResolvedTimezone>, timestamp: &NaiveDateTime) -> LocalizedTime {
+    // a place without coordinates or country has no temperature data either, so the timezone is irrelevant
+    let timezone = timezone.map_or(Tz::UTC, |resolved| resolved.timezone);
+
+    geo_time::localize(&timezone, timestamp)
 }
 
 
-async fn get_single_temperature(client: &reqwest::Client, place: &Place, timestamp: Option<NaiveDateTime>) -> Result<SingleTemperature, Error> {
+async fn get_single_temperature(client: &reqwest::Client, place: &Place, timestamp: Option<&DateTime<Tz>>)
+    -> Result<SingleTemperature, Error>
+{
     let maybe_coordinates: Option<Coordinates> = place.into();
+    // request the temperature at the exact elevation of summits instead of the surrounding terrain
+    let elevation = place.elevation();
 
     match maybe_coordinates {
         Some(coordinates) => {
             match timestamp {
-                Some(unlocalized_datetime) => {
-                    let timezone = geo_time::get_timezone(&coordinates);
-
-                    // include place-local timezone information in the requested datetime
-                    let localized_datetime = timezone
-                        .map(|tz| unlocalized_datetime.and_local_timezone(tz))
-                        .unwrap_or(Tz::Europe__Vienna.from_local_datetime(&unlocalized_datetime))
-                        .single();
-
-                    let localized_datetime = localized_datetime.ok_or_else(|| Error::Unexpected {
-                        reason: "Could not localize timestamp to timezone of place!".to_string(),
-                        subject: Some(format!("Place: {:?}, timezone: {:?}", place, timezone))
-                    })?;
-
-                    let data = historic::get_past_temperature(client, &coordinates, &localized_datetime).await?;
+                Some(localized_datetime) => {
+                    let data = historic::get_past_temperature(client, &coordinates, elevation, localized_datetime).await?;
                     Ok(data)
                 }
                 None => {
-                    let data = forecast::get_current_temperature(client, &coordinates).await?;
+                    let data = forecast::get_current_temperature(client, &coordinates, elevation).await?;
                     Ok(data)
                 }
             }
@@ -116,90 +188,20 @@ async fn get_single_temperature(client: &reqwest::Client, place: &Place, timesta
     }
 }
 
-fn create_temperature_response(place: &Place, data: SingleTemperature) -> String {
-    let last_updated_info = localize_raw!("last-updated", unix_time: data.epoch);
+fn create_temperature_response(ctx: Context<'_>, place: &Place, data: SingleTemperature, language: &LanguageIdentifier) -> String {
+    let last_updated_info = localize_raw!(ctx, "last-updated", unix_time: data.epoch);
+
+    let place_info = match place.elevation() {
+        Some(elevation) => localize_raw!(ctx, "place-with-elevation",
+            place: place.address_details(language),
+            elevation: elevation.round()
+        ),
+        None => place.address_details(language),
+    };
 
-    localize!("temperature-current-success",
-        place: place.address_details(),
-        celcius: data.temperature_2m,
+    localize!(ctx, "temperature-current-success",
+        place: place_info,
+        celsius: data.temperature_2m,
         last_updated: last_updated_info
     )
 }
-
-// If first element matches the search term exactly and the second element does not, take the first one. Else, show the full list to pick from.
-async fn select_place<'a>(ctx: Context<'_>, places: &'a [Place]) -> Selection<&'a Place> {
-    if places.is_empty() {
-        return Selection::Failed(Error::Unexpected {
-            reason: "Received an empty set of place options.".to_string(),
-            subject: None
-        });
-    }
-
-    if places.len() == 1 {
-        return Selection::Unique(&places[0]);
-    }
-
-    request_user_selection(ctx, places).await
-}
-
-async fn request_user_selection<'a>(ctx: Context<'_>, places: &'a [Place]) -> Selection<&'a Place> {
-    const INTERACTION_ID: &str = "place_selection";
-
-    let options: Vec<MenuOption> = places.iter().enumerate()
-        .map(|(idx, p)| {
-            let mut place_string = p.to_string();
-            // discord limits the length of a menu option to 100 characters
-            truncate_ellipsis(&mut place_string, 100, "...");
-            MenuOption::new(place_string, idx.to_string())
-        })
-        .collect();
-
-    // create select place prompt with the selection menu
-    let place_selection = {
-        let components = vec![
-            serenity::CreateActionRow::SelectMenu(
-                serenity::CreateSelectMenu::new(
-                    INTERACTION_ID,
-                    CreateSelectMenuKind::String { options })
-                    .placeholder("Select place")
-            ),
-        ];
-
-        CreateReply::default()
-            .content(localize!("place-selection-which-one"))
-            .components(components)
-            .ephemeral(true)
-            .reply(true)
-    };
-
-    if let Err(e) = ctx.send(place_selection).await {
-        return Selection::Failed(e.into());
-    }
-
-    // react on the first interaction on the selection menu (with timeout)
-    if let Some(interaction) = serenity::ComponentInteractionCollector::new(ctx)
-        .author_id(ctx.author().id)
-        .channel_id(ctx.channel_id())
-        .timeout(std::time::Duration::from_secs(120))
-        .filter(move |inter| inter.data.custom_id == INTERACTION_ID)
-        .await
-    {
-        let selected_value = match &interaction.data.kind {
-            serenity::ComponentInteractionDataKind::StringSelect { values} => &values[0],
-            _ => panic!("unexpected interaction data kind"),
-        };
-
-        // acknowledge the interaction
-        let _ = interaction.create_response(ctx, serenity::CreateInteractionResponse::Acknowledge).await;
-        interaction.delete_response(ctx.http()).await.ok();
-
-        if let Ok(index) = selected_value.parse::<usize>() {
-            if let Some(place) = places.get(index) {
-                return Selection::OneOfMany(place);
-            }
-        };
-    }
-
-    // only reached if the interaction collector reaches its timeout
-    Selection::Aborted
-}
\ No newline at end of file
@@ -10,7 +10,7 @@ pub async fn help(
 ) -> Result<(), Error> {
 
     let config = poise::builtins::HelpConfiguration {
-        extra_text_at_bottom: &localize!("help-footer"),
+        extra_text_at_bottom: &localize!(ctx, "help-footer"),
         ..Default::default()
     };
 
@@ -26,10 +26,10 @@ pub async fn age(ctx: Context<'_>,
 ) -> Result<(), Error> {
 
     let u = user.as_ref().unwrap_or_else(|| ctx.author());
-    let discord_timestamp = localize_raw!("age-timestamp",
+    let discord_timestamp = localize_raw!(ctx, "age-timestamp",
         unix_time: u.created_at().unix_timestamp()
     );
-    let response = localize!("age-account-created-at",
+    let response = localize!(ctx, "age-account-created-at",
         username: u.display_name(),
         timestamp: discord_timestamp
     );
@@ -1,2 +1,6 @@
 pub mod climate;
-pub mod general;
\ No newline at end of file
+pub mod general;
+pub mod places;
+pub mod settings;
+pub mod time;
+pub mod world_clock;
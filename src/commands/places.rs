@@ -0,0 +1,666 @@
+use super::time::{describe_timezone_source, place_timezone};
+use crate::localization::*;
+use crate::sources::common::{truncate_ellipsis, Coordinates};
+use crate::sources::geo_time;
+use crate::sources::nominatim::{FeatureKind, Place};
+use crate::sources::place_query::PlaceQuery;
+use crate::sources::place_ranking::{self, RankingContext};
+use crate::storage::settings::SavedPlace;
+use crate::utils::{parsing, text};
+use crate::{Context, Error};
+
+use fluent_templates::LanguageIdentifier;
+use lazy_static::lazy_static;
+use log::warn;
+use poise::{serenity_prelude as serenity, ChoiceParameter, CreateReply};
+use serenity::{AutocompleteChoice, CreateSelectMenuKind, CreateSelectMenuOption as MenuOption, Mention, UserId};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Alias of the saved place that is used whenever a command needs a place but none was given.
+pub const HOME_PLACE: &str = "home";
+const MAX_SAVED_PLACES: usize = 25;
+// number of places picked from selection menus that are remembered to rank future searches
+const MAX_RECENT_PLACES: usize = 20;
+pub const RESELECTION_ID: &str = "place_reselection";
+// how long the buttons below a response can be clicked
+const BUTTON_TIMEOUT: Duration = Duration::from_secs(120);
+
+// marks autocompleted place arguments, which carry the reference of the chosen place
+const AUTOCOMPLETE_PREFIX: &str = "ref:";
+const AUTOCOMPLETE_MIN_CHARS: usize = 3;
+const AUTOCOMPLETE_DEBOUNCE: Duration = Duration::from_millis(400);
+const AUTOCOMPLETE_DEADLINE: Duration = Duration::from_millis(2400);
+
+lazy_static! {
+    // number of the latest pending autocomplete request of every user
+    static ref AUTOCOMPLETE_REQUESTS: Mutex<HashMap<UserId, u64>> = Mutex::new(HashMap::new());
+}
+static AUTOCOMPLETE_REQUEST_NUMBER: AtomicU64 = AtomicU64::new(0);
+
+/// Manage your saved places
+#[poise::command(slash_command, prefix_command, subcommands("add", "list", "remove"), subcommand_required)]
+pub async fn places(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Save a place under a name of your choice, e.g. 'home'
+#[poise::command(slash_command, prefix_command)]
+pub async fn add(ctx: Context<'_>,
+                 #[description = "Name to save the place under"] name: String,
+                 #[description = "Name of a place, coordinates or a Plus Code"]
+                 #[autocomplete = "autocomplete_place"] #[rest] place: String,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id.get();
+    let key = text::normalize(&name);
+
+    let saved = saved_places(ctx);
+    if !saved.contains_key(&key) && saved.len() >= MAX_SAVED_PLACES {
+        ctx.reply(localize!(ctx, "places-limit-reached", limit: MAX_SAVED_PLACES)).await?;
+        return Ok(());
+    }
+
+    let places = find_places(ctx, &place, FeatureKind::default(), None).await?;
+    if places.is_empty() {
+        ctx.reply(localize!(ctx, "place-not-found", search_term: &place)).await?;
+        return Ok(());
+    }
+
+    let (selected, is_reply) = match select_place(ctx, &places, &place).await {
+        Selection::Unique(place) | Selection::Remembered(place) => (place, true),
+        Selection::OneOfMany(place) => (place, false),
+        Selection::Aborted => {
+            ctx.channel_id().say(ctx.http(), localize!(ctx, "place-selection-timeout")).await?;
+            return Ok(());
+        },
+        Selection::Failed(error) => return Err(error),
+    };
+
+    ctx.data().user_settings.update(user_id, |settings| {
+        settings.saved_places.insert(key, SavedPlace { alias: name.clone(), place: selected.clone() });
+    })?;
+
+    let response = localize!(ctx, "places-saved", place: selected.address_details(&query_language(ctx)), alias: &name);
+    if is_reply {
+        ctx.reply(response).await?;
+    } else {
+        // the selection prompt was the reply, so mention the user in a new message instead
+        let response = localize!(ctx, "response-invoked-by", message: response, user_mention: Mention::User(ctx.author().id));
+        ctx.channel_id().say(ctx.http(), response).await?;
+    }
+
+    Ok(())
+}
+
+/// List all of your saved places
+#[poise::command(slash_command, prefix_command)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let saved = saved_places(ctx);
+
+    let language = query_language(ctx);
+
+    let response = if saved.is_empty() {
+        localize!(ctx, "places-none-saved")
+    } else {
+        let entries = saved.values()
+            .map(|saved| localize_raw!(ctx, "places-list-entry",
+                alias: &saved.alias,
+                place: saved.place.address_details(&language)
+            ))
+            .collect::<Vec<_>>()
+            .join("\n");
+        localize!(ctx, "places-list", entries: entries)
+    };
+
+    ctx.send(CreateReply::default().content(response).ephemeral(true).reply(true)).await?;
+    Ok(())
+}
+
+/// Remove one of your saved places
+#[poise::command(slash_command, prefix_command)]
+pub async fn remove(ctx: Context<'_>,
+                    #[description = "Name of the saved place"]
+                    #[autocomplete = "autocomplete_saved_place"] #[rest] name: String,
+) -> Result<(), Error> {
+    let key = text::normalize(&name);
+    let mut removed = None;
+
+    ctx.data().user_settings.update(ctx.author().id.get(), |settings| {
+        removed = settings.saved_places.remove(&key);
+    })?;
+
+    match removed {
+        Some(saved) => ctx.reply(localize!(ctx, "places-removed", alias: saved.alias)).await?,
+        None => ctx.reply(localize!(ctx, "places-not-saved", alias: &name)).await?,
+    };
+
+    Ok(())
+}
+
+/// Show information about a place
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn place(ctx: Context<'_>,
+                   #[description = "Name of a place, coordinates, a Plus Code or one of your saved places"]
+                   #[autocomplete = "autocomplete_place"] #[rest] name: String,
+) -> Result<(), Error> {
+    let language = query_language(ctx);
+    let mut resolution = Resolution::default();
+
+    // runs again with the place the user chose differently instead of the remembered one
+    loop {
+        let Some(place) = resolve_place(ctx, &name, &mut resolution).await? else {
+            return Ok(());
+        };
+        let embed = create_place_embed(ctx, &place, &language);
+        if !respond_with_embed(ctx, String::new(), Some(embed), &mut resolution).await? {
+            return Ok(());
+        }
+    }
+}
+
+fn create_place_embed(ctx: Context<'_>, place: &Place, language: &LanguageIdentifier) -> serenity::CreateEmbed {
+    // discord limits the value of an embed field to 1024 characters
+    const MAX_FIELD_LENGTH: usize = 1024;
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("{} {}", place.country_indicator(), place.localized_name(language)))
+        .description(place.address_details(language));
+    let tags = place.extratags.clone().unwrap_or_default();
+
+    if let Some(population) = place.population() {
+        let population = match &tags.population_date {
+            Some(date) => localize_raw!(ctx, "place-info-population-dated", population: population, date: date),
+            None => localize_raw!(ctx, "place-info-population-amount", population: population),
+        };
+        embed = embed.field(localize!(ctx, "place-info-population"), population, true);
+    }
+
+    if let Some(capital) = tags.capital.as_deref() {
+        // the capital tag is either 'yes' or the admin level of the area the place is the capital of
+        let capital = match capital {
+            "yes" | "2" => localize!(ctx, "place-info-capital-country"),
+            "3" | "4" => localize!(ctx, "place-info-capital-state"),
+            _ => localize!(ctx, "place-info-capital-region"),
+        };
+        embed = embed.field(localize!(ctx, "place-info-capital"), capital, true);
+    }
+
+    if let Some(elevation) = place.elevation() {
+        embed = embed.field(localize!(ctx, "place-info-elevation"), format!("{} m", elevation.round()), true);
+    }
+
+    if let Some(resolved) = place_timezone(place) {
+        let mut timezone = localize_raw!(ctx, "place-info-timezone-offset",
+            timezone: resolved.timezone.name(),
+            offset: geo_time::current_offset(&resolved.timezone)
+        );
+        if let Some(note) = describe_timezone_source(ctx, &resolved) {
+            timezone = format!("{}\n{}", timezone, note);
+        }
+        embed = embed.field(localize!(ctx, "place-info-timezone"), timezone, true);
+    }
+
+    if let Some(coordinates) = Option::<Coordinates>::from(place) {
+        embed = embed.field(localize!(ctx, "place-info-coordinates"), coordinates.to_string(), true);
+    }
+
+    let links: Vec<String> = [
+        ("Wikipedia".to_string(), tags.wikipedia_url()),
+        ("Wikidata".to_string(), tags.wikidata_url()),
+        ("OpenStreetMap".to_string(), place.osm_url()),
+        (localize_raw!(ctx, "place-info-website"), tags.website.clone()),
+    ].into_iter()
+        .filter_map(|(label, url)| url.map(|url| format!("[{}]({})", label, url)))
+        .collect();
+    if !links.is_empty() {
+        embed = embed.field(localize!(ctx, "place-info-links"), links.join(" · "), false);
+    }
+
+    let mut names = place.name.translations().into_iter()
+        .filter(|(_, name)| **name != place.name.local)
+        .map(|(language, name)| format!("`{}` {}", language, name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if !names.is_empty() {
+        truncate_ellipsis(&mut names, MAX_FIELD_LENGTH, "...");
+        embed = embed.field(localize!(ctx, "place-info-names"), names, false);
+    }
+
+    embed
+}
+
+/// Returns the place the invoking user saved under the given `alias`, if any.
+pub fn saved_place(ctx: Context<'_>, alias: &str) -> Option<SavedPlace> {
+    saved_places(ctx).remove(&text::normalize(alias))
+}
+
+fn saved_places(ctx: Context<'_>) -> BTreeMap<String, SavedPlace> {
+    ctx.data().user_settings.get(&ctx.author().id.get())
+        .map(|settings| settings.saved_places)
+        .unwrap_or_default()
+}
+
+/// Resolves the user `input` to a list of candidate places. Saved places and places picked from the
+/// autocompletion are used right away, coordinates are only looked up to describe their surroundings
+/// and anything else is searched by name, optionally within the given `country`.
+pub async fn find_places(ctx: Context<'_>, input: &str, kind: FeatureKind, country: Option<&str>)
+    -> Result<Vec<Place>, Error>
+{
+    if let Some(saved) = saved_place(ctx, input) {
+        return Ok(vec![saved.place]);
+    }
+
+    let client = &ctx.data().http_client;
+    let requester = ctx.author().id.get();
+    let geocoder = &ctx.data().geocoder;
+    let language = query_language(ctx);
+
+    let places = if let Some(coordinates) = parsing::parse_coordinates(input) {
+        vec![geocoder.locate_coordinates(client, requester, &coordinates, &language).await?]
+    } else if let Some(reference) = input.strip_prefix(AUTOCOMPLETE_PREFIX) {
+        geocoder.lookup_place(client, requester, reference, &language).await?
+    } else {
+        let region = ctx.data().search_region(ctx.guild_id());
+        let query = PlaceQuery::parse(input).with_country(country);
+        geocoder.query_place(client, requester, &query, kind, &region, &language).await?
+    };
+
+    Ok(places)
+}
+
+pub async fn autocomplete_place(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let partial = partial.trim();
+
+    // the user's saved places always come first
+    let mut choices = autocomplete_saved_place(ctx, partial).await;
+
+    // coordinates don't need to be looked up and very short inputs match too many places
+    if partial.chars().count() < AUTOCOMPLETE_MIN_CHARS || parsing::parse_coordinates(partial).is_some() {
+        return choices;
+    }
+
+    // wait until the user stopped typing, so only the last keystroke triggers a search
+    if !debounce_autocomplete(ctx.author().id).await {
+        return choices;
+    }
+
+    let region = ctx.data().search_region(ctx.guild_id());
+    let language = query_language(ctx);
+    let query = PlaceQuery::parse(partial).with_country(selected_country(ctx).as_deref());
+    let query = ctx.data().geocoder.autocomplete_place(&ctx.data().http_client, ctx.author().id.get(), &query,
+                                                       selected_kind(ctx), &region, &language);
+
+    // discord discards autocomplete responses after 3 seconds, so don't wait in the rate limiter queue any longer
+    let places = match tokio::time::timeout(AUTOCOMPLETE_DEADLINE, query).await {
+        Ok(Ok(places)) => places,
+        Ok(Err(e)) => {
+            warn!("Failed to autocomplete place '{}': {}", partial, e);
+            return choices;
+        },
+        Err(_) => return choices,
+    };
+
+    choices.extend(places.iter()
+        .filter_map(|place| {
+            let mut label = place.label(&language);
+            // discord limits the length of an autocomplete choice to 100 characters
+            truncate_ellipsis(&mut label, 100, "...");
+            place.reference()
+                .map(|reference| AutocompleteChoice::new(label, format!("{}{}", AUTOCOMPLETE_PREFIX, reference)))
+        }));
+
+    choices
+}
+
+async fn autocomplete_saved_place(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let partial = text::normalize(partial);
+    let language = query_language(ctx);
+
+    saved_places(ctx).into_iter()
+        .filter(|(key, _)| key.starts_with(&partial))
+        .map(|(_, saved)| {
+            let mut label = format!("⭐ {} | {}", saved.alias, saved.place.label(&language));
+            truncate_ellipsis(&mut label, 100, "...");
+            AutocompleteChoice::new(label, saved.alias)
+        })
+        .collect()
+}
+
+/// Returns `true` if no other autocomplete request of the same user arrived during the debounce period.
+async fn debounce_autocomplete(user: UserId) -> bool {
+    let request_number = AUTOCOMPLETE_REQUEST_NUMBER.fetch_add(1, Ordering::Relaxed);
+    AUTOCOMPLETE_REQUESTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(user, request_number);
+
+    tokio::time::sleep(AUTOCOMPLETE_DEBOUNCE).await;
+
+    let mut requests = AUTOCOMPLETE_REQUESTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let latest = requests.get(&user) == Some(&request_number);
+    // the latest request is done waiting, so the user doesn't need to be tracked until they type again
+    if latest {
+        requests.remove(&user);
+    }
+    latest
+}
+
+// the kind option may already be filled in while the user is still typing the place
+fn selected_kind(ctx: Context<'_>) -> FeatureKind {
+    let Context::Application(app_ctx) = ctx else {
+        return FeatureKind::default();
+    };
+
+    app_ctx.args.iter()
+        .find(|option| option.name == "kind")
+        .and_then(|option| match option.value {
+            serenity::ResolvedValue::Integer(index) => FeatureKind::from_index(index as usize),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+// like the kind, the country option may already be filled in while the user is typing the place
+fn selected_country(ctx: Context<'_>) -> Option<String> {
+    let Context::Application(app_ctx) = ctx else {
+        return None;
+    };
+
+    app_ctx.args.iter()
+        .find(|option| option.name == "country")
+        .and_then(|option| match option.value {
+            serenity::ResolvedValue::String(country) => Some(country.to_string()),
+            _ => None,
+        })
+}
+
+pub enum Selection<T> {
+    Unique(T),
+    OneOfMany(T),
+    /// the place the user picked the last time they searched for the same term
+    Remembered(T),
+    Aborted,
+    Failed(Error),
+}
+
+
+// Rank the places by how well they match the search term and the user's habits. If the best one is a clear
+// winner, take it. Else, show the ranked list to pick from.
+pub async fn select_place<'a>(ctx: Context<'_>, places: &'a [Place], search_term: &str) -> Selection<&'a Place> {
+    if places.is_empty() {
+        return Selection::Failed(Error::Unexpected {
+            reason: "Received an empty set of place options.".to_string(),
+            subject: None
+        });
+    }
+
+    let user_id = ctx.author().id.get();
+    let settings = ctx.data().user_settings.get(&user_id).unwrap_or_default();
+
+    let remembered = settings.place_choices.get(&text::normalize(search_term))
+        .and_then(|reference| places.iter().find(|place| place.reference().as_ref() == Some(reference)));
+    if let Some(place) = remembered {
+        return Selection::Remembered(place);
+    }
+
+    let previous_choices = settings.recent_places;
+    let region = ctx.data().search_region(ctx.guild_id());
+
+    let ranking = place_ranking::rank(places, &RankingContext {
+        search_term,
+        region: &region,
+        previous_choices: &previous_choices,
+    });
+
+    if let Some(place) = ranking.clear_winner() {
+        return Selection::Unique(place);
+    }
+
+    let selection = request_user_selection(ctx, ranking.ordered()).await;
+    if let Selection::OneOfMany(place) = &selection {
+        remember_choice(ctx, search_term, place);
+    }
+
+    selection
+}
+
+/// Lets the user pick a different place than the one taken automatically, e.g. after they clicked the
+/// [reselection_button]. The ranked places are offered in a selection menu.
+pub async fn reselect_place<'a>(ctx: Context<'_>, places: &'a [Place], search_term: &str) -> Selection<&'a Place> {
+    let user_id = ctx.author().id.get();
+    let previous_choices = ctx.data().user_settings.get(&user_id)
+        .map(|settings| settings.recent_places)
+        .unwrap_or_default();
+    let region = ctx.data().search_region(ctx.guild_id());
+
+    let ranking = place_ranking::rank(places, &RankingContext {
+        search_term,
+        region: &region,
+        previous_choices: &previous_choices,
+    });
+
+    let selection = request_user_selection(ctx, ranking.ordered()).await;
+    if let Selection::OneOfMany(place) = &selection {
+        remember_choice(ctx, search_term, place);
+    }
+
+    selection
+}
+
+/// How the places of a single command invocation were resolved, see [resolve_place] and [respond].
+#[derive(Default)]
+pub struct Resolution {
+    /// the user picked a place from a selection menu, which then was the reply to the command
+    prompted: bool,
+    /// inputs that were resolved to the place the user picked for them before
+    remembered: Vec<String>,
+    /// inputs the user wants to pick a different place for than the remembered one
+    reselecting: Vec<String>,
+}
+
+/// Resolves the user `input` to a single place, letting the user pick one if necessary. If no place
+/// could be found or the selection timed out, the user is told so and `None` is returned.
+pub async fn resolve_place(ctx: Context<'_>, input: &str, resolution: &mut Resolution) -> Result<Option<Place>, Error> {
+    let places = find_places(ctx, input, FeatureKind::default(), None).await?;
+    if places.is_empty() {
+        ctx.reply(localize!(ctx, "place-not-found", search_term: input)).await?;
+        return Ok(None);
+    }
+
+    let selection = match resolution.reselecting.iter().any(|reselecting| reselecting == input) {
+        true => reselect_place(ctx, &places, input).await,
+        false => select_place(ctx, &places, input).await,
+    };
+    match selection {
+        Selection::Unique(place) => Ok(Some(place.clone())),
+        Selection::Remembered(place) => {
+            resolution.remembered.push(input.to_string());
+            Ok(Some(place.clone()))
+        },
+        Selection::OneOfMany(place) => {
+            resolution.prompted = true;
+            Ok(Some(place.clone()))
+        },
+        Selection::Aborted => {
+            ctx.channel_id().say(ctx.http(), localize!(ctx, "place-selection-timeout")).await?;
+            Ok(None)
+        },
+        Selection::Failed(error) => Err(error),
+    }
+}
+
+/// Sends the `response` to a command whose places were resolved by [resolve_place]. If a remembered
+/// place was taken, the user may still choose a different one. Returns `true` if they did, so the
+/// command has to resolve its places and respond again.
+pub async fn respond(ctx: Context<'_>, response: String, resolution: &mut Resolution) -> Result<bool, Error> {
+    respond_with_embed(ctx, response, None, resolution).await
+}
+
+/// Like [respond], with an embed below the `response`, which may be empty then.
+pub async fn respond_with_embed(ctx: Context<'_>, response: String, embed: Option<serenity::CreateEmbed>,
+                                resolution: &mut Resolution) -> Result<bool, Error>
+{
+    let buttons = match resolution.remembered.is_empty() {
+        true => vec![],
+        false => vec![reselection_button(ctx)],
+    };
+
+    let mut sent = if resolution.prompted {
+        // the selection prompt was the reply, so mention the user in a new message instead
+        let content = match response.is_empty() {
+            true => Mention::User(ctx.author().id).to_string(),
+            false => localize!(ctx, "response-invoked-by", message: response, user_mention: Mention::User(ctx.author().id)),
+        };
+        let mut message = serenity::CreateMessage::new().content(content).components(button_row(buttons.clone()));
+        if let Some(embed) = embed {
+            message = message.embed(embed);
+        }
+        SentResponse::Message(Box::new(ctx.channel_id().send_message(ctx.http(), message).await?))
+    } else {
+        let mut reply = CreateReply::default().components(button_row(buttons.clone())).reply(true);
+        if !response.is_empty() {
+            reply = reply.content(response);
+        }
+        if let Some(embed) = embed {
+            reply = reply.embed(embed);
+        }
+        SentResponse::Reply(ctx.send(reply).await?)
+    };
+
+    if buttons.is_empty() || sent.await_button(ctx).await?.as_deref() != Some(RESELECTION_ID) {
+        return Ok(false);
+    }
+    resolution.reselecting = std::mem::take(&mut resolution.remembered);
+    Ok(true)
+}
+
+/// A response that was already sent, whose buttons are still waiting for a click.
+pub enum SentResponse<'a> {
+    Reply(poise::ReplyHandle<'a>),
+    Message(Box<serenity::Message>),
+}
+impl SentResponse<'_> {
+    /// Waits for a click on one of the buttons and returns its ID. The buttons are removed afterwards in any case.
+    pub async fn await_button(&mut self, ctx: Context<'_>) -> Result<Option<String>, Error> {
+        let message_id = match self {
+            SentResponse::Reply(reply) => reply.message().await?.id,
+            SentResponse::Message(message) => message.id,
+        };
+
+        let interaction = serenity::ComponentInteractionCollector::new(ctx)
+            .author_id(ctx.author().id)
+            .message_id(message_id)
+            .timeout(BUTTON_TIMEOUT)
+            .await;
+
+        let _ = match self {
+            SentResponse::Reply(reply) => reply.edit(ctx, CreateReply::default().components(vec![])).await,
+            SentResponse::Message(message) => message.edit(ctx, serenity::EditMessage::new().components(vec![])).await,
+        };
+
+        Ok(match interaction {
+            Some(interaction) => {
+                let _ = interaction.create_response(ctx, serenity::CreateInteractionResponse::Acknowledge).await;
+                Some(interaction.data.custom_id)
+            },
+            None => None,
+        })
+    }
+}
+
+pub fn button_row(buttons: Vec<serenity::CreateButton>) -> Vec<serenity::CreateActionRow> {
+    if buttons.is_empty() {
+        return vec![];
+    }
+    vec![serenity::CreateActionRow::Buttons(buttons)]
+}
+
+pub fn reselection_button(ctx: Context<'_>) -> serenity::CreateButton {
+    serenity::CreateButton::new(RESELECTION_ID)
+        .label(localize!(ctx, "place-choose-different"))
+        .style(serenity::ButtonStyle::Secondary)
+}
+
+// remember the choice, so the same place is taken for this search term and preferred in all other searches
+fn remember_choice(ctx: Context<'_>, search_term: &str, place: &Place) {
+    let Some(reference) = place.reference() else {
+        return;
+    };
+    let user_id = ctx.author().id.get();
+
+    let remembered = ctx.data().user_settings.update(user_id, |settings| {
+        settings.place_choices.insert(text::normalize(search_term), reference.clone());
+
+        settings.recent_places.retain(|r| *r != reference);
+        settings.recent_places.insert(0, reference);
+        settings.recent_places.truncate(MAX_RECENT_PLACES);
+        // choices of places that were not picked for a long time are forgotten along with them
+        settings.place_choices.retain(|_, reference| settings.recent_places.contains(reference));
+    });
+
+    if let Err(e) = remembered {
+        warn!("Failed to remember place choice of user {}: {}", user_id, e);
+    }
+}
+
+async fn request_user_selection<'a>(ctx: Context<'_>, places: Vec<&'a Place>) -> Selection<&'a Place> {
+    const INTERACTION_ID: &str = "place_selection";
+
+    let language = query_language(ctx);
+    let options: Vec<MenuOption> = places.iter().enumerate()
+        .map(|(idx, p)| {
+            let mut place_string = p.label(&language);
+            // discord limits the length of a menu option to 100 characters
+            truncate_ellipsis(&mut place_string, 100, "...");
+            MenuOption::new(place_string, idx.to_string())
+        })
+        .collect();
+
+    // create select place prompt with the selection menu
+    let place_selection = {
+        let components = vec![
+            serenity::CreateActionRow::SelectMenu(
+                serenity::CreateSelectMenu::new(
+                    INTERACTION_ID,
+                    CreateSelectMenuKind::String { options })
+                    .placeholder(localize!(ctx, "place-selection-placeholder"))
+            ),
+        ];
+
+        CreateReply::default()
+            .content(localize!(ctx, "place-selection-which-one"))
+            .components(components)
+            .ephemeral(true)
+            .reply(true)
+    };
+
+    if let Err(e) = ctx.send(place_selection).await {
+        return Selection::Failed(e.into());
+    }
+
+    // react on the first interaction on the selection menu (with timeout)
+    if let Some(interaction) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(std::time::Duration::from_secs(120))
+        .filter(move |inter| inter.data.custom_id == INTERACTION_ID)
+        .await
+    {
+        let selected_value = match &interaction.data.kind {
+            serenity::ComponentInteractionDataKind::StringSelect { values} => &values[0],
+            _ => panic!("unexpected interaction data kind"),
+        };
+
+        // acknowledge the interaction
+        let _ = interaction.create_response(ctx, serenity::CreateInteractionResponse::Acknowledge).await;
+        interaction.delete_response(ctx.http()).await.ok();
+
+        if let Ok(index) = selected_value.parse::<usize>() {
+            if let Some(place) = places.get(index) {
+                return Selection::OneOfMany(*place);
+            }
+        };
+    }
+
+    // only reached if the interaction collector reaches its timeout
+    Selection::Aborted
+}
\ No newline at end of file
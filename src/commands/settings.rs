@@ -0,0 +1,122 @@
+use crate::localization::*;
+use crate::sources::nominatim::{SearchRegion, SearchRegionError};
+use crate::{Context, Error};
+
+use fluent_templates::LanguageIdentifier;
+
+/// Configure the bot for this server or yourself
+#[poise::command(slash_command, prefix_command, subcommands("region", "language", "server_language"), subcommand_required)]
+pub async fn settings(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show or change the region that place searches on this server prefer
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn region(ctx: Context<'_>,
+                    #[description = "'none', country codes like 'AT, DE' or a box like '55.0, 5.3; 45.8, 17.4'"]
+                    #[rest] region: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(()); // unreachable due to guild_only
+    };
+
+    let Some(input) = region else {
+        let current = ctx.data().search_region(Some(guild_id));
+        ctx.reply(localize!(ctx, "region-current", region: current)).await?;
+        return Ok(());
+    };
+
+    match input.parse::<SearchRegion>() {
+        Ok(region) => {
+            ctx.data().guild_settings.update(guild_id.get(), |settings| {
+                settings.search_region = region.clone();
+            })?;
+            ctx.reply(localize!(ctx, "region-updated", region: region)).await?;
+        },
+        Err(SearchRegionError::Invalid) => {
+            ctx.reply(localize!(ctx, "region-invalid", input: input)).await?;
+        },
+        Err(SearchRegionError::UnknownCountries(codes)) => {
+            ctx.reply(localize!(ctx, "region-unknown-countries", codes: codes.join(", "))).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show or change the language the bot talks to you in
+#[poise::command(slash_command, prefix_command)]
+pub async fn language(ctx: Context<'_>,
+                      #[description = "A language like 'de' or 'en', or 'none' to follow your Discord language"]
+                      language: Option<String>,
+) -> Result<(), Error> {
+    let Some(input) = language else {
+        ctx.reply(localize!(ctx, "language-current", language: user_language(ctx))).await?;
+        return Ok(());
+    };
+
+    let language = match parse_language_choice(&input) {
+        Ok(language) => language,
+        Err(()) => {
+            ctx.reply(localize!(ctx, "language-unsupported", input: input, available: available_languages())).await?;
+            return Ok(());
+        }
+    };
+    ctx.data().user_settings.update(ctx.author().id.get(), |settings| {
+        settings.language = language.as_ref().map(ToString::to_string);
+    })?;
+
+    // answer in the new language right away
+    match language {
+        Some(language) => ctx.reply(localize!(ctx, "language-updated", language: language)).await?,
+        None => ctx.reply(localize!(ctx, "language-reset")).await?,
+    };
+    Ok(())
+}
+
+/// Show or change the language for members of this server who have not chosen one
+#[poise::command(slash_command, prefix_command, rename = "server-language", guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn server_language(ctx: Context<'_>,
+                             #[description = "A language like 'de' or 'en', or 'none' for the default"]
+                             language: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(()); // unreachable due to guild_only
+    };
+
+    let Some(input) = language else {
+        let current = guild_language(ctx.data(), Some(guild_id));
+        ctx.reply(localize!(ctx, "server-language-current", language: current)).await?;
+        return Ok(());
+    };
+
+    let language = match parse_language_choice(&input) {
+        Ok(language) => language,
+        Err(()) => {
+            ctx.reply(localize!(ctx, "language-unsupported", input: input, available: available_languages())).await?;
+            return Ok(());
+        }
+    };
+    ctx.data().guild_settings.update(guild_id.get(), |settings| {
+        settings.language = language.as_ref().map(ToString::to_string);
+    })?;
+
+    let current = guild_language(ctx.data(), Some(guild_id));
+    ctx.reply(localize!(ctx, "server-language-updated", language: current)).await?;
+    Ok(())
+}
+
+// `none` removes the choice, anything else has to be one of the available locales
+fn parse_language_choice(input: &str) -> Result<Option<LanguageIdentifier>, ()> {
+    match input.trim().to_lowercase().as_str() {
+        "none" | "reset" | "default" => Ok(None),
+        language => supported_language(language).map(Some).ok_or(()),
+    }
+}
+
+fn available_languages() -> String {
+    crate::LOCALES.locales()
+        .map(|locale| format!("`{}`", locale))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
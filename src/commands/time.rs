@@ -0,0 +1,519 @@
+use super::places::{autocomplete_place, resolve_place, respond, saved_place, Resolution, HOME_PLACE};
+use crate::localization::*;
+use crate::sources::common::Coordinates;
+use crate::sources::geo_time::{self, LocalizedTime, ResolvedTimezone, TimezoneSource, Tz};
+use crate::sources::nominatim::Place;
+use crate::utils::parsing;
+use crate::{Context, Error};
+
+use chrono::{DateTime, NaiveTime, Offset, TimeDelta, Utc};
+use fluent_templates::LanguageIdentifier;
+use std::ops::Range;
+
+const WORK_START_HOUR: u32 = 9;
+const WORK_END_HOUR: u32 = 17;
+
+/// Show the current local time of a place
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn time(ctx: Context<'_>,
+                  #[description = "Also show when the clocks changed last and when they change next"]
+                  clock_changes: Option<bool>,
+                  #[description = "Name of a place, coordinates, a Plus Code or one of your saved places"]
+                  #[autocomplete = "autocomplete_place"] #[rest] place: Option<String>,
+) -> Result<(), Error> {
+    // default to the user's home if no place is given
+    let place = match place {
+        Some(place) => place,
+        None if saved_place(ctx, HOME_PLACE).is_some() => HOME_PLACE.to_string(),
+        None => {
+            ctx.reply(localize!(ctx, "place-missing-no-home", home: HOME_PLACE)).await?;
+            return Ok(());
+        }
+    };
+
+    let mut resolution = Resolution::default();
+    // runs again with the place the user chose differently instead of the remembered one
+    loop {
+        let Some(place) = resolve_place(ctx, &place, &mut resolution).await? else {
+            return Ok(());
+        };
+        let now = Utc::now();
+        let mut response = create_time_response(ctx, &place, &now, &query_language(ctx));
+        if clock_changes.unwrap_or(false) {
+            if let Some(resolved) = place_timezone(&place) {
+                response = format!("{}\n{}", response, describe_transitions_around(ctx, &resolved.timezone, &now));
+            }
+        }
+        if !respond(ctx, response, &mut resolution).await? {
+            return Ok(());
+        }
+    }
+}
+
+fn create_time_response(ctx: Context<'_>, place: &Place, now: &DateTime<Utc>, language: &LanguageIdentifier)
+    -> String
+{
+    let Some(resolved) = place_timezone(place) else {
+        return localize!(ctx, "time-timezone-unknown", place: place.address_details(language));
+    };
+    let timezone = resolved.timezone;
+    let local = now.with_timezone(&timezone);
+
+    let mut response = localize!(ctx, "time-current",
+        place: place.address_details(language),
+        time: local.format(&localize_raw!(ctx, "format-time")),
+        date: local.date_naive(),
+        timezone: timezone.name(),
+        abbreviation: local.format("%Z"),
+        offset: local.offset().fix()
+    );
+    if let Some(note) = describe_timezone_source(ctx, &resolved) {
+        response = format!("{}\n{}", response, note);
+    }
+
+    // compare to the user's own timezone, which is known from their home
+    let home_timezone = saved_place(ctx, HOME_PLACE)
+        .and_then(|home| place_timezone(&home.place))
+        .map(|resolved| resolved.timezone);
+    if let Some(home_timezone) = home_timezone {
+        let difference = local.offset().fix().local_minus_utc()
+            - now.with_timezone(&home_timezone).offset().fix().local_minus_utc();
+
+        let comparison = match difference {
+            0 => localize!(ctx, "time-difference-none"),
+            d if d > 0 => localize!(ctx, "time-difference-ahead", difference: d),
+            d => localize!(ctx, "time-difference-behind", difference: -d),
+        };
+        response = format!("{}\n{}", response, comparison);
+    }
+
+    response
+}
+
+// the styles Discord can render a timestamp in, see https://discord.com/developers/docs/reference#message-formatting
+const TIMESTAMP_STYLES: [char; 7] = ['t', 'T', 'd', 'D', 'f', 'F', 'R'];
+
+/// Create Discord timestamps for a local time at a place, which everyone sees in their own timezone
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn timestamp(ctx: Context<'_>,
+                       #[description = "Time of day, e.g. 19:00 or 7pm"] time: String,
+                       #[description = "Date, e.g. 24.12.2024 or Friday, today by default"] date: Option<String>,
+                       #[description = "Place the time refers to, your home by default"]
+                       #[autocomplete = "autocomplete_place"] #[rest] place: Option<String>,
+) -> Result<(), Error> {
+    let time = match parsing::parse_time(&time) {
+        Ok(time) => time,
+        Err(e) => {
+            ctx.reply(localize!(ctx, "timestamp-parse-error", error: &e)).await?;
+            return Err(Error::Handled { inner: Box::new(e) });
+        }
+    };
+
+    // default to the user's home if no place is given
+    let place = match place {
+        Some(place) => place,
+        None if saved_place(ctx, HOME_PLACE).is_some() => HOME_PLACE.to_string(),
+        None => {
+            ctx.reply(localize!(ctx, "place-missing-no-home", home: HOME_PLACE)).await?;
+            return Ok(());
+        }
+    };
+
+    let mut resolution = Resolution::default();
+    // runs again with the place the user chose differently instead of the remembered one
+    loop {
+        let Some(place) = resolve_place(ctx, &place, &mut resolution).await? else {
+            return Ok(());
+        };
+        let language = query_language(ctx);
+        let Some(resolved) = place_timezone(&place) else {
+            ctx.reply(localize!(ctx, "time-timezone-unknown", place: place.address_details(&language))).await?;
+            return Ok(());
+        };
+
+        // without a date, the time refers to the current day at the place
+        let today = Utc::now().with_timezone(&resolved.timezone).date_naive();
+        let date = match date.as_deref().map(|date| parsing::parse_date(date, today)).transpose() {
+            Ok(date) => date.unwrap_or(today),
+            Err(e) => {
+                ctx.reply(localize!(ctx, "timestamp-parse-error", error: &e)).await?;
+                return Err(Error::Handled { inner: Box::new(e) });
+            }
+        };
+
+        let localized = geo_time::localize(&resolved.timezone, &date.and_time(time));
+        let instant = localized.instant();
+        let unix_time = instant.timestamp();
+
+        let styles: Vec<String> = TIMESTAMP_STYLES.iter()
+            .map(|style| localize_raw!(ctx, "timestamp-style",
+                style: style,
+                code: format!("`<t:{}:{}>`", unix_time, style),
+                preview: format!("<t:{}:{}>", unix_time, style)
+            ))
+            .collect();
+
+        let mut response = localize!(ctx, "timestamp-success",
+            time: instant.format(&localize_raw!(ctx, "format-time")),
+            date: instant.date_naive(),
+            abbreviation: instant.format("%Z"),
+            place: place.address_details(&language),
+            styles: styles.join("\n")
+        );
+        let notes = [describe_timezone_source(ctx, &resolved), describe_localization(ctx, &localized)];
+        for note in notes.into_iter().flatten() {
+            response = format!("{}\n{}", response, note);
+        }
+
+        if !respond(ctx, response, &mut resolution).await? {
+            return Ok(());
+        }
+    }
+}
+
+/// Convert between the timezones of places
+#[poise::command(slash_command, prefix_command, subcommands("convert_time"), subcommand_required)]
+pub async fn convert(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Convert a time of day from one place to the local time of other places
+#[poise::command(slash_command, prefix_command, rename = "time")]
+#[allow(clippy::too_many_arguments)] // every argument is a slash command option
+pub async fn convert_time(ctx: Context<'_>,
+                          #[description = "Time of day to convert, e.g. 15:30 or 3pm"] time: String,
+                          #[description = "Place the time refers to"]
+                          #[autocomplete = "autocomplete_place"] from: String,
+                          #[description = "Place to convert the time to"]
+                          #[autocomplete = "autocomplete_place"] to: String,
+                          #[description = "Another place to convert the time to"]
+                          #[autocomplete = "autocomplete_place"] to_2: Option<String>,
+                          #[description = "Another place to convert the time to"]
+                          #[autocomplete = "autocomplete_place"] to_3: Option<String>,
+                          #[description = "Another place to convert the time to"]
+                          #[autocomplete = "autocomplete_place"] to_4: Option<String>,
+                          #[description = "Date of the time, today at the first place by default"] date: Option<String>,
+) -> Result<(), Error> {
+    let time = match parsing::parse_time(&time) {
+        Ok(time) => time,
+        Err(e) => {
+            ctx.reply(localize!(ctx, "timestamp-parse-error", error: &e)).await?;
+            return Err(Error::Handled { inner: Box::new(e) });
+        }
+    };
+
+    let mut resolution = Resolution::default();
+    // runs again with the places the user chose differently instead of the remembered ones
+    loop {
+        let Some(source) = resolve_place(ctx, &from, &mut resolution).await? else {
+            return Ok(());
+        };
+        let Some(source_timezone) = place_timezone(&source).map(|resolved| resolved.timezone) else {
+            ctx.reply(localize!(ctx, "time-timezone-unknown", place: source.address_details(&query_language(ctx)))).await?;
+            return Ok(());
+        };
+
+        // without a date, the time refers to the current day at the source place
+        let today = Utc::now().with_timezone(&source_timezone).date_naive();
+        let date = match date.as_deref().map(|date| parsing::parse_date(date, today)).transpose() {
+            Ok(date) => date.unwrap_or(today),
+            Err(e) => {
+                ctx.reply(localize!(ctx, "timestamp-parse-error", error: &e)).await?;
+                return Err(Error::Handled { inner: Box::new(e) });
+            }
+        };
+
+        let localized = geo_time::localize(&source_timezone, &date.and_time(time));
+        let instant = localized.instant();
+
+        let mut targets = Vec::new();
+        for input in [Some(&to), to_2.as_ref(), to_3.as_ref(), to_4.as_ref()].into_iter().flatten() {
+            match resolve_place(ctx, input, &mut resolution).await? {
+                Some(place) => targets.push(place),
+                None => return Ok(()),
+            }
+        }
+
+        let language = query_language(ctx);
+        let format_at = |place: &Place| match place_timezone(place) {
+            Some(ResolvedTimezone { timezone, .. }) => {
+                let local = instant.with_timezone(&timezone);
+                localize_raw!(ctx, "convert-time-entry",
+                    time: local.format(&localize_raw!(ctx, "format-time")),
+                    date: local.date_naive(),
+                    place: place.address_summary(&language),
+                    abbreviation: local.format("%Z")
+                )
+            },
+            None => localize_raw!(ctx, "time-timezone-unknown", place: place.address_summary(&language)),
+        };
+
+        let mut response = localize!(ctx, "convert-time-success",
+            source: format_at(&source),
+            targets: targets.iter().map(|place| format!("\\- {}", format_at(place))).collect::<Vec<_>>().join("\n"),
+            unix_time: instant.timestamp()
+        );
+        if let Some(note) = describe_localization(ctx, &localized) {
+            response = format!("{}\n{}", response, note);
+        }
+
+        if !respond(ctx, response, &mut resolution).await? {
+            return Ok(());
+        }
+    }
+}
+
+/// Find times during working hours at all of the given places
+#[poise::command(slash_command, prefix_command)]
+#[allow(clippy::too_many_arguments)] // every argument is a slash command option
+pub async fn meeting(ctx: Context<'_>,
+                     #[description = "First place of a participant"]
+                     #[autocomplete = "autocomplete_place"] place_1: String,
+                     #[description = "Second place of a participant"]
+                     #[autocomplete = "autocomplete_place"] place_2: String,
+                     #[description = "Another place of a participant"]
+                     #[autocomplete = "autocomplete_place"] place_3: Option<String>,
+                     #[description = "Another place of a participant"]
+                     #[autocomplete = "autocomplete_place"] place_4: Option<String>,
+                     #[description = "Day of the meeting at the first place, today by default"] date: Option<String>,
+                     #[description = "Start of the working hours, 9:00 by default"] start: Option<String>,
+                     #[description = "End of the working hours, 17:00 by default"] end: Option<String>,
+) -> Result<(), Error> {
+    let parse_time_or = |input: Option<String>, default_hour: u32| match input {
+        Some(input) => parsing::parse_time(&input),
+        None => Ok(NaiveTime::from_hms_opt(default_hour, 0, 0).unwrap_or_default()),
+    };
+    let working_hours = match (parse_time_or(start, WORK_START_HOUR), parse_time_or(end, WORK_END_HOUR)) {
+        (Ok(start), Ok(end)) if start < end => start..end,
+        (Ok(start), Ok(end)) => {
+            ctx.reply(localize!(ctx, "meeting-invalid-hours",
+                start: start.format(&localize_raw!(ctx, "format-time")),
+                end: end.format(&localize_raw!(ctx, "format-time"))
+            )).await?;
+            return Ok(());
+        },
+        (Err(e), _) | (_, Err(e)) => {
+            ctx.reply(localize!(ctx, "timestamp-parse-error", error: &e)).await?;
+            return Err(Error::Handled { inner: Box::new(e) });
+        },
+    };
+
+    let mut resolution = Resolution::default();
+    // runs again with the places the user chose differently instead of the remembered ones
+    loop {
+        let mut participants: Vec<(String, Tz)> = Vec::new();
+        let language = query_language(ctx);
+
+        for input in [Some(&place_1), Some(&place_2), place_3.as_ref(), place_4.as_ref()].into_iter().flatten() {
+            let Some(place) = resolve_place(ctx, input, &mut resolution).await? else {
+                return Ok(());
+            };
+            let Some(timezone) = place_timezone(&place).map(|resolved| resolved.timezone) else {
+                ctx.reply(localize!(ctx, "time-timezone-unknown", place: place.address_details(&language))).await?;
+                return Ok(());
+            };
+            participants.push((place.localized_name(&language).to_string(), timezone));
+        }
+
+        // the day is the one at the first place, which may have 23 or 25 hours due to DST
+        let first_timezone = participants[0].1;
+        let today = Utc::now().with_timezone(&first_timezone).date_naive();
+        let date = match date.as_deref().map(|date| parsing::parse_date(date, today)).transpose() {
+            Ok(date) => date.unwrap_or(today),
+            Err(e) => {
+                ctx.reply(localize!(ctx, "timestamp-parse-error", error: &e)).await?;
+                return Err(Error::Handled { inner: Box::new(e) });
+            }
+        };
+        let day_start = geo_time::start_of_day(&first_timezone, date);
+        let day_end = date.succ_opt()
+            .map(|next_day| geo_time::start_of_day(&first_timezone, next_day))
+            .unwrap_or(day_start + TimeDelta::days(1));
+
+        let slots: Vec<DateTime<Utc>> = std::iter::successors(Some(day_start), |slot| Some(*slot + TimeDelta::hours(1)))
+            .take_while(|slot| *slot < day_end)
+            .collect();
+
+        let mut response = localize!(ctx, "meeting-success",
+            date: date,
+            grid: render_meeting_grid(&participants, &slots, &working_hours),
+            overlaps: describe_overlaps(ctx, &participants, &slots, &working_hours)
+        );
+        if let Some(transitions) = describe_transitions(ctx, &participants, day_start, day_end) {
+            response = format!("{}\n{}", response, transitions);
+        }
+
+        if !respond(ctx, response, &mut resolution).await? {
+            return Ok(());
+        }
+    }
+}
+
+// whether the whole hour starting at `slot` lies within the working hours of the timezone
+fn is_working_hour(slot: &DateTime<Utc>, timezone: &Tz, working_hours: &Range<NaiveTime>) -> bool {
+    // the local end of the hour is calculated separately, since the clocks may be changed within the hour
+    let local_start = slot.with_timezone(timezone);
+    let local_end = (*slot + TimeDelta::hours(1)).with_timezone(timezone);
+
+    local_start.date_naive() == local_end.date_naive()
+        && working_hours.start <= local_start.time()
+        && local_end.time() <= working_hours.end
+}
+
+fn is_shared_working_hour(slot: &DateTime<Utc>, participants: &[(String, Tz)], working_hours: &Range<NaiveTime>) -> bool {
+    participants.iter().all(|(_, timezone)| is_working_hour(slot, timezone, working_hours))
+}
+
+// a table with one column per place and one row per hour, in which the rows within everyone's working
+// hours are highlighted in green by Discord's diff syntax highlighting
+fn render_meeting_grid(participants: &[(String, Tz)], slots: &[DateTime<Utc>], working_hours: &Range<NaiveTime>)
+    -> String
+{
+    const MAX_NAME_LENGTH: usize = 10;
+    const CELL_WIDTH: usize = 7; // "HH:MM" plus a marker and a space
+
+    let names: Vec<String> = participants.iter()
+        .map(|(name, _)| name.chars().take(MAX_NAME_LENGTH).collect())
+        .collect();
+    let widths: Vec<usize> = names.iter().map(|name| name.chars().count().max(CELL_WIDTH)).collect();
+
+    let mut lines = vec![format!("  {}", names.iter().zip(&widths)
+        .map(|(name, width)| format!("{:<width$}", name, width = width))
+        .collect::<Vec<_>>()
+        .join(" "))];
+
+    for slot in slots {
+        let cells = participants.iter().zip(&widths)
+            .map(|((_, timezone), width)| {
+                let marker = if is_working_hour(slot, timezone, working_hours) { '•' } else { ' ' };
+                let cell = format!("{}{}", slot.with_timezone(timezone).format("%H:%M"), marker);
+                format!("{:<width$}", cell, width = width)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let prefix = if is_shared_working_hour(slot, participants, working_hours) { '+' } else { ' ' };
+        lines.push(format!("{} {}", prefix, cells.trim_end()));
+    }
+
+    format!("```diff\n{}\n```", lines.join("\n"))
+}
+
+// the shared working hours as ranges of Discord timestamps, so everyone sees them in their own time
+fn describe_overlaps(ctx: Context<'_>, participants: &[(String, Tz)], slots: &[DateTime<Utc>], working_hours: &Range<NaiveTime>)
+    -> String
+{
+    let mut ranges: Vec<Range<DateTime<Utc>>> = Vec::new();
+
+    for slot in slots.iter().filter(|slot| is_shared_working_hour(slot, participants, working_hours)) {
+        let slot_end = *slot + TimeDelta::hours(1);
+        match ranges.last_mut() {
+            Some(range) if range.end == *slot => range.end = slot_end,
+            _ => ranges.push(*slot..slot_end),
+        }
+    }
+
+    if ranges.is_empty() {
+        return localize_raw!(ctx, "meeting-no-overlap");
+    }
+
+    let ranges = ranges.iter()
+        .map(|range| localize_raw!(ctx, "meeting-overlap-range",
+            start: range.start.timestamp(),
+            end: range.end.timestamp()
+        ))
+        .collect::<Vec<_>>()
+        .join(", ");
+    localize_raw!(ctx, "meeting-overlap", ranges: ranges)
+}
+
+// mention clock changes on that day, since they shift the local times within the grid
+fn describe_transitions(ctx: Context<'_>, participants: &[(String, Tz)], day_start: DateTime<Utc>, day_end: DateTime<Utc>)
+    -> Option<String>
+{
+    let offset_at = |instant: DateTime<Utc>, timezone: &Tz| instant.with_timezone(timezone).offset().fix();
+
+    let changing: Vec<&str> = participants.iter()
+        .filter(|(_, timezone)| offset_at(day_start, timezone) != offset_at(day_end - TimeDelta::seconds(1), timezone))
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    match changing.as_slice() {
+        [] => None,
+        places => Some(localize!(ctx, "meeting-clock-change", places: places.join(", "))),
+    }
+}
+
+// e.g. "the clocks were turned forward from 02:00 CET to 03:00 CEST" for the last and next transition
+fn describe_transitions_around(ctx: Context<'_>, timezone: &Tz, now: &DateTime<Utc>) -> String {
+    let describe = |transition: &geo_time::Transition, key: &str| {
+        let time_format = localize_raw!(ctx, "format-time");
+        let local_after = transition.instant.with_timezone(timezone);
+        localize_raw!(ctx, key,
+            direction: if transition.is_forward() { "forward" } else { "back" },
+            date: local_after.date_naive(),
+            time_before: transition.instant.with_timezone(&transition.offset_before).format(&time_format),
+            time_after: local_after.format(&time_format),
+            abbreviation_before: &transition.abbreviation_before,
+            abbreviation_after: &transition.abbreviation_after,
+            offset_before: transition.offset_before,
+            offset_after: transition.offset_after,
+            unix_time: transition.instant.timestamp()
+        )
+    };
+
+    let previous = geo_time::previous_transition(timezone, now);
+    let next = geo_time::next_transition(timezone, now);
+    if previous.is_none() && next.is_none() {
+        return localize!(ctx, "time-transition-none", timezone: timezone.name());
+    }
+
+    [previous.map(|t| describe(&t, "time-transition-previous")), next.map(|t| describe(&t, "time-transition-next"))]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Tells the user if the requested time had to be adjusted due to a DST transition.
+pub fn describe_localization(ctx: Context<'_>, localized: &LocalizedTime) -> Option<String> {
+    let time_format = localize_raw!(ctx, "format-time");
+
+    match localized {
+        LocalizedTime::Exact(_) => None,
+        LocalizedTime::Shifted { requested, shifted } => Some(localize!(ctx, "dst-gap-note",
+            requested: requested.format(&time_format),
+            shifted: shifted.format(&time_format),
+            abbreviation: shifted.format("%Z")
+        )),
+        LocalizedTime::Ambiguous { earlier, .. } => Some(localize!(ctx, "dst-ambiguous-note",
+            time: earlier.format(&time_format),
+            abbreviation: earlier.format("%Z")
+        )),
+    }
+}
+
+/// Returns the timezone the place lies in, falling back to an approximation if its coordinates are
+/// not within any timezone. Without coordinates, only the country of the place is considered.
+pub fn place_timezone(place: &Place) -> Option<ResolvedTimezone> {
+    let country_code = place.address.country_code.as_deref();
+
+    match Option::<Coordinates>::from(place) {
+        Some(coordinates) => Some(geo_time::resolve_timezone(&coordinates, country_code)),
+        None => country_code.and_then(geo_time::get_country_timezone)
+            .map(|timezone| ResolvedTimezone { timezone, source: TimezoneSource::Country }),
+    }
+}
+
+/// Tells the user that the timezone is only an approximation, if it is one.
+pub fn describe_timezone_source(ctx: Context<'_>, resolved: &ResolvedTimezone) -> Option<String> {
+    let timezone = resolved.timezone.name();
+
+    match resolved.source {
+        TimezoneSource::Exact => None,
+        TimezoneSource::NearestLand => Some(localize!(ctx, "timezone-fallback-nearest-land", timezone: timezone)),
+        TimezoneSource::Country => Some(localize!(ctx, "timezone-fallback-country", timezone: timezone)),
+        TimezoneSource::Longitude => Some(localize!(ctx, "timezone-fallback-longitude", timezone: timezone)),
+    }
+}
+
@@ -0,0 +1,159 @@
+use super::places::{resolve_place, Resolution};
+use super::time::place_timezone;
+use crate::localization::*;
+use crate::storage::settings::WorldClock;
+use crate::storage::PersistentMap;
+use crate::{Context, Error};
+
+use chrono::{DateTime, TimeDelta, Timelike, Utc};
+use fluent_templates::LanguageIdentifier;
+use log::{info, warn};
+use poise::serenity_prelude::{self as serenity, CreateMessage, EditMessage};
+use poise::CreateReply;
+use std::ops::Range;
+use std::sync::Arc;
+
+const MAX_PLACES: usize = 10;
+// local hours shown as daytime, a rough approximation that ignores latitude and season
+const DAYTIME_HOURS: Range<u32> = 7..19;
+
+/// Post a clock showing the current time at several places, which is kept up to date
+#[poise::command(slash_command, prefix_command, rename = "worldclock", subcommands("post", "remove"),
+    subcommand_required, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn world_clock(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Post a world clock in this channel
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn post(ctx: Context<'_>,
+                  #[description = "Places separated by semicolons, e.g. Vienna; New York; Tokyo"]
+                  #[rest] places: String,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(()); // unreachable due to guild_only
+    };
+
+    // place searches may contain commas themselves, e.g. `Graz, AT`
+    let inputs: Vec<&str> = places.split(';').map(str::trim).filter(|input| !input.is_empty()).collect();
+    if inputs.is_empty() || inputs.len() > MAX_PLACES {
+        ctx.reply(localize!(ctx, "world-clock-invalid-count", max: MAX_PLACES)).await?;
+        return Ok(());
+    }
+
+    let mut resolution = Resolution::default();
+    let mut places = Vec::new();
+    for input in inputs {
+        match resolve_place(ctx, input, &mut resolution).await? {
+            Some(place) => places.push(place),
+            None => return Ok(()),
+        }
+    }
+
+    let clock = WorldClock {
+        guild_id: guild_id.get(),
+        channel_id: ctx.channel_id().get(),
+        places,
+        language: user_language(ctx).to_string(),
+    };
+    // the clock is a message of its own rather than a reply, since it outlives this command
+    let message = CreateMessage::new().content(render_world_clock(&clock, &Utc::now()));
+    let message = ctx.channel_id().send_message(ctx.http(), message).await?;
+    ctx.data().world_clocks.insert(message.id.get(), clock)?;
+
+    ctx.send(CreateReply::default()
+        .content(localize!(ctx, "world-clock-posted", link: message.link()))
+        .ephemeral(true)
+    ).await?;
+
+    Ok(())
+}
+
+/// Stop updating a world clock and delete it
+#[poise::command(slash_command, prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn remove(ctx: Context<'_>,
+                    #[description = "Link or ID of the world clock message"] message: String,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(()); // unreachable due to guild_only
+    };
+
+    // message links end with the message id, e.g. https://discord.com/channels/<guild>/<channel>/<message>
+    let message_id = message.trim().trim_end_matches('/').rsplit('/').next()
+        .and_then(|id| id.parse::<u64>().ok());
+    let clock = message_id.and_then(|id| ctx.data().world_clocks.get(&id).map(|clock| (id, clock)));
+
+    match clock {
+        // clocks of other guilds must not be removable from here
+        Some((message_id, clock)) if clock.guild_id == guild_id.get() => {
+            ctx.data().world_clocks.remove(&message_id)?;
+            // the message may already have been deleted by hand
+            let _ = serenity::ChannelId::new(clock.channel_id)
+                .delete_message(ctx.http(), serenity::MessageId::new(message_id)).await;
+            ctx.reply(localize!(ctx, "world-clock-removed")).await?;
+        },
+        _ => {
+            ctx.reply(localize!(ctx, "world-clock-not-found", input: message)).await?;
+        },
+    }
+
+    Ok(())
+}
+
+/// Updates all world clocks at the start of every minute, so the shown times change right when the
+/// actual ones do. Clocks whose message was deleted or became inaccessible are forgotten.
+pub async fn keep_updated(http: Arc<serenity::Http>, clocks: Arc<PersistentMap<u64, WorldClock>>) {
+    loop {
+        // the clocks are updated right away, since they are outdated after a restart
+        update_all(&http, &clocks).await;
+
+        let now = Utc::now();
+        let next_minute = now.with_second(0).and_then(|time| time.with_nanosecond(0))
+            .map_or(now, |minute| minute + TimeDelta::minutes(1));
+        let wait = (next_minute - now).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+    }
+}
+
+async fn update_all(http: &serenity::Http, clocks: &PersistentMap<u64, WorldClock>) {
+    let now = Utc::now();
+
+    for (message_id, clock) in clocks.entries() {
+        let content = EditMessage::new().content(render_world_clock(&clock, &now));
+        let result = serenity::ChannelId::new(clock.channel_id)
+            .edit_message(http, serenity::MessageId::new(message_id), content).await;
+
+        match result {
+            Ok(_) => (),
+            Err(serenity::Error::Http(e)) if e.status_code().is_some_and(|status| [403, 404].contains(&status.as_u16())) => {
+                info!("Removing world clock {}, since its message is gone: {}", message_id, e);
+                if let Err(e) = clocks.remove(&message_id) {
+                    warn!("Failed to remove world clock {}: {}", message_id, e);
+                }
+            },
+            Err(e) => warn!("Failed to update world clock {}: {}", message_id, e),
+        }
+    }
+}
+
+fn render_world_clock(clock: &WorldClock, now: &DateTime<Utc>) -> String {
+    let language: LanguageIdentifier = clock.language.parse().unwrap_or(crate::FALLBACK_LANGUAGE);
+
+    let lines: Vec<String> = clock.places.iter()
+        .map(|place| match place_timezone(place) {
+            Some(resolved) => {
+                let local = now.with_timezone(&resolved.timezone);
+                localize_raw!(language, "world-clock-entry",
+                    indicator: if DAYTIME_HOURS.contains(&local.hour()) { "☀️" } else { "🌙" },
+                    place: place.localized_name(&language),
+                    time: local.format(&localize_raw!(language, "format-time")),
+                    abbreviation: local.format("%Z"),
+                    date: local.date_naive()
+                )
+            },
+            None => localize_raw!(language, "time-timezone-unknown", place: place.localized_name(&language)),
+        })
+        .collect();
+
+    localize!(language, "world-clock", clocks: lines.join("\n"), unix_time: now.timestamp())
+}
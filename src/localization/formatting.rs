@@ -0,0 +1,253 @@
+use chrono::{DateTime, NaiveDate};
+use fluent_bundle::types::{FluentNumber, FluentNumberOptions};
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_templates::{FluentBundle, LanguageIdentifier};
+use intl_memoizer::concurrent::IntlLangMemoizer;
+use intl_memoizer::Memoizable;
+
+// languages writing 12,3 instead of 12.3, which are grouped like 1.234.567 instead of 1,234,567
+const DECIMAL_COMMA_LANGUAGES: [&str; 16] = [
+    "de", "da", "es", "fr", "hr", "it", "lt", "nl", "no", "pl", "pt", "ro", "ru", "tr", "uk", "cs",
+];
+// tried one after another, so regional patterns have to come before those of the whole language
+const DATE_PATTERNS: [(&str, &str); 5] = [
+    ("en-US", "%m/%d/%Y"),
+    ("en", "%d/%m/%Y"),
+    ("de", "%d.%m.%Y"),
+    ("fr", "%d/%m/%Y"),
+    ("it", "%d/%m/%Y"),
+];
+const DEFAULT_DATE_PATTERN: &str = "%Y-%m-%d";
+
+/// How numbers and dates are written in a language.
+#[derive(Debug, Clone)]
+pub struct LocaleFormat {
+    decimal_separator: char,
+    grouping_separator: char,
+    date_pattern: &'static str,
+}
+impl LocaleFormat {
+    pub fn new(language: &LanguageIdentifier) -> LocaleFormat {
+        let decimal_comma = DECIMAL_COMMA_LANGUAGES.contains(&language.language.as_str());
+        let date_pattern = DATE_PATTERNS.iter()
+            .find(|(locale, _)| locale.parse::<LanguageIdentifier>().is_ok_and(|locale| {
+                locale.language == language.language && locale.region.is_none_or(|region| Some(region) == language.region)
+            }))
+            .map_or(DEFAULT_DATE_PATTERN, |(_, pattern)| pattern);
+
+        LocaleFormat {
+            decimal_separator: if decimal_comma { ',' } else { '.' },
+            grouping_separator: if decimal_comma { '.' } else { ',' },
+            date_pattern,
+        }
+    }
+
+    /// Writes the number with the separators of the language, respecting the fraction digits and
+    /// grouping of its options.
+    pub fn format_number(&self, number: &FluentNumber) -> String {
+        let options = &number.options;
+        let mut digits = match options.maximum_fraction_digits {
+            Some(maximum) => format!("{:.*}", maximum, number.value),
+            None => number.value.abs().to_string(),
+        };
+        digits = digits.trim_start_matches('-').to_string();
+
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        // trailing zeros beyond the minimum are left out, e.g. 12.30 becomes 12.3
+        let minimum_fraction = options.minimum_fraction_digits.unwrap_or(0);
+        let mut fraction = fraction.trim_end_matches('0').to_string();
+        while fraction.len() < minimum_fraction {
+            fraction.push('0');
+        }
+        let integer = format!("{:0>width$}", integer, width = options.minimum_integer_digits.unwrap_or(1));
+
+        let mut formatted = String::new();
+        if number.value < 0.0 && (integer.chars().chain(fraction.chars()).any(|digit| digit != '0')) {
+            formatted.push('-');
+        }
+        for (i, digit) in integer.chars().enumerate() {
+            let remaining = integer.len() - i;
+            if options.use_grouping && i > 0 && remaining % 3 == 0 {
+                formatted.push(self.grouping_separator);
+            }
+            formatted.push(digit);
+        }
+        if !fraction.is_empty() {
+            formatted.push(self.decimal_separator);
+            formatted.push_str(&fraction);
+        }
+
+        formatted
+    }
+
+    pub fn format_date(&self, date: &NaiveDate) -> String {
+        date.format(self.date_pattern).to_string()
+    }
+}
+impl Memoizable for LocaleFormat {
+    type Args = ();
+    type Error = ();
+
+    fn construct(language: LanguageIdentifier, _args: Self::Args) -> Result<Self, Self::Error> {
+        Ok(LocaleFormat::new(&language))
+    }
+}
+
+/// Numbers passed to messages are neither grouped nor rounded, since they are often identifiers like
+/// Unix timestamps. Use `NUMBER($value, useGrouping: "true")` to group the digits.
+pub fn number_options() -> FluentNumberOptions {
+    FluentNumberOptions { use_grouping: false, ..Default::default() }
+}
+
+/// Adds functions to the `bundle` that format their values the way its language does:
+/// - `NUMBER($value, ...)` with the options of the Fluent specification, e.g. `maximumFractionDigits`
+/// - `TEMPERATURE($celsius)`, e.g. `12.3°C`
+/// - `DURATION($seconds)`, e.g. `5 h 30 min`
+/// - `DATE($date)` for ISO 8601 dates like `2024-03-31` or Unix timestamps, e.g. `31.03.2024`
+pub fn add_functions<R>(bundle: &mut FluentBundle<R>) {
+    let format = bundle.locales.first().map(LocaleFormat::new)
+        .unwrap_or_else(|| LocaleFormat::new(&crate::FALLBACK_LANGUAGE));
+    let results = [
+        bundle.add_function("NUMBER", number),
+        bundle.add_function("TEMPERATURE", {
+            let format = format.clone();
+            move |positional, _named| temperature(&format, positional)
+        }),
+        bundle.add_function("DURATION", {
+            let format = format.clone();
+            move |positional, _named| duration(&format, positional)
+        }),
+        bundle.add_function("DATE", move |positional, _named| date(&format, positional)),
+    ];
+
+    for error in results.into_iter().filter_map(Result::err) {
+        log::error!("Failed to add a function to the localizations of {:?}: {:?}", bundle.locales, error);
+    }
+}
+
+/// Writes numbers the way the language of the bundle does, to be set as formatter of every bundle.
+pub fn format_value(value: &FluentValue, intls: &IntlLangMemoizer) -> Option<String> {
+    match value {
+        FluentValue::Number(number) => intls
+            .with_try_get::<LocaleFormat, _, _>((), |format| format.format_number(number))
+            .ok(),
+        _ => None,
+    }
+}
+
+fn number<'a>(positional: &[FluentValue<'a>], named: &FluentArgs) -> FluentValue<'a> {
+    match positional.first() {
+        Some(FluentValue::Number(number)) => {
+            // as in the specification, digits are grouped unless the message says otherwise
+            let mut options = FluentNumberOptions { use_grouping: true, ..number.options.clone() };
+            options.merge(named);
+            FluentValue::Number(FluentNumber::new(number.value, options))
+        },
+        Some(FluentValue::String(text)) => FluentValue::try_number(text).into_owned(),
+        _ => FluentValue::Error,
+    }
+}
+
+fn temperature<'a>(format: &LocaleFormat, positional: &[FluentValue<'a>]) -> FluentValue<'a> {
+    match positional.first() {
+        Some(FluentValue::Number(celsius)) => {
+            let options = FluentNumberOptions {
+                minimum_fraction_digits: Some(1),
+                maximum_fraction_digits: Some(1),
+                ..number_options()
+            };
+            let celsius = FluentNumber::new(celsius.value, options);
+            FluentValue::String(format!("{}°C", format.format_number(&celsius)).into())
+        },
+        _ => FluentValue::Error,
+    }
+}
+
+// e.g. "5 h 30 min" for 19800 seconds, the sign is left to the message
+fn duration<'a>(format: &LocaleFormat, positional: &[FluentValue<'a>]) -> FluentValue<'a> {
+    let Some(FluentValue::Number(seconds)) = positional.first() else {
+        return FluentValue::Error;
+    };
+    let seconds = seconds.value.abs().round() as i64;
+    let (hours, minutes) = (seconds / 3600, (seconds % 3600) / 60);
+    let number = |value: i64| format.format_number(&FluentNumber::new(value as f64, number_options()));
+
+    let formatted = match (hours, minutes) {
+        (0, minutes) => format!("{} min", number(minutes)),
+        (hours, 0) => format!("{} h", number(hours)),
+        (hours, minutes) => format!("{} h {} min", number(hours), number(minutes)),
+    };
+    FluentValue::String(formatted.into())
+}
+
+fn date<'a>(format: &LocaleFormat, positional: &[FluentValue<'a>]) -> FluentValue<'a> {
+    let date = match positional.first() {
+        Some(FluentValue::String(text)) => NaiveDate::parse_from_str(text, "%Y-%m-%d").ok(),
+        Some(FluentValue::Number(unix_time)) => DateTime::from_timestamp(unix_time.value as i64, 0)
+            .map(|instant| instant.date_naive()),
+        _ => None,
+    };
+
+    match date {
+        Some(date) => FluentValue::String(format.format_date(&date).into()),
+        None => FluentValue::Error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(language: &str, value: f64, options: FluentNumberOptions) -> String {
+        let language: LanguageIdentifier = language.parse().unwrap();
+        LocaleFormat::new(&language).format_number(&FluentNumber::new(value, options))
+    }
+
+    #[test]
+    fn separators_depend_on_language() {
+        let grouped = || FluentNumberOptions { use_grouping: true, ..Default::default() };
+        assert_eq!(format("en-UK", 1234567.5, grouped()), "1,234,567.5");
+        assert_eq!(format("de", 1234567.5, grouped()), "1.234.567,5");
+        assert_eq!(format("de", 123.25, number_options()), "123,25");
+    }
+
+    #[test]
+    fn grouping() {
+        let grouped = || FluentNumberOptions { use_grouping: true, ..Default::default() };
+        assert_eq!(format("en-UK", 123.0, grouped()), "123");
+        assert_eq!(format("en-UK", 1234.0, grouped()), "1,234");
+        assert_eq!(format("en-UK", 123456.0, grouped()), "123,456");
+        assert_eq!(format("en-UK", -1234.0, grouped()), "-1,234");
+        assert_eq!(format("en-UK", 1711843200.0, number_options()), "1711843200");
+    }
+
+    #[test]
+    fn rounding() {
+        let rounded = |digits| FluentNumberOptions { maximum_fraction_digits: Some(digits), ..number_options() };
+        assert_eq!(format("en-UK", 12.345, rounded(1)), "12.3");
+        assert_eq!(format("en-UK", 12.96, rounded(1)), "13");
+        assert_eq!(format("en-UK", 2.5, rounded(0)), "2");
+        assert_eq!(format("en-UK", 12.30, rounded(2)), "12.3");
+    }
+
+    #[test]
+    fn negative_zero() {
+        let rounded = FluentNumberOptions { maximum_fraction_digits: Some(1), ..number_options() };
+        assert_eq!(format("en-UK", -0.04, rounded.clone()), "0");
+        assert_eq!(format("en-UK", -0.0, number_options()), "0");
+        assert_eq!(format("en-UK", -0.06, rounded), "-0.1");
+    }
+
+    #[test]
+    fn minimum_digits() {
+        let options = FluentNumberOptions {
+            minimum_integer_digits: Some(2),
+            minimum_fraction_digits: Some(2),
+            maximum_fraction_digits: Some(3),
+            ..number_options()
+        };
+        assert_eq!(format("en-UK", 5.0, options.clone()), "05.00");
+        assert_eq!(format("de", 5.1234, options.clone()), "05,123");
+        assert_eq!(format("en-UK", 123.4, options), "123.40");
+    }
+}
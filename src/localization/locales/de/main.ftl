@@ -1,11 +1,249 @@
 # Command metadata
-welcome = begrüßen
+command-welcome = begrüßen
     .description = Begrüßt einen Nutzer
     .user = nutzer
     .user-description = Der zu begrüßende Nutzer
     .message = nachricht
     .message-description = Die versendete Nachricht
 
+command-help = hilfe
+    .description = Zeigt eine Übersicht aller Kommandos
+    .command = kommando
+    .command-description = Kommando, zu dem Hilfe angezeigt werden soll
+command-age = alter
+    .description = Zeigt, wann dein Konto oder das eines anderen Nutzers erstellt wurde
+    .user = nutzer
+    .user-description = Der gewählte Nutzer
+command-temperature = temperatur
+    .description = Zeigt die Temperatur an einem Ort
+    .place = ort
+    .place-description = Name eines Ortes, Koordinaten, ein Plus Code oder einer deiner gespeicherten Orte
+    .date = datum
+    .date-description = Ein bestimmtes Datum in der Vergangenheit
+    .time = uhrzeit
+    .time-description = Eine bestimmte Uhrzeit
+    .kind = art
+    .kind-description = Art des gesuchten Ortes
+    .country = land
+    .country-description = Name oder ISO-Code des Landes, in dem der Ort gesucht wird
+command-places = orte
+    .description = Verwaltet deine gespeicherten Orte
+command-places-add = hinzufügen
+    .description = Speichert einen Ort unter einem Namen deiner Wahl, z.B. 'zuhause'
+    .name = name
+    .name-description = Name, unter dem der Ort gespeichert wird
+    .place = ort
+    .place-description = Name eines Ortes, Koordinaten oder ein Plus Code
+command-places-list = liste
+    .description = Zeigt deine gespeicherten Orte
+command-places-remove = entfernen
+    .description = Entfernt einen deiner gespeicherten Orte
+    .name = name
+    .name-description = Name des gespeicherten Ortes
+command-place = ort
+    .description = Zeigt Informationen zu einem Ort
+    .name = name
+    .name-description = Name eines Ortes, Koordinaten, ein Plus Code oder einer deiner gespeicherten Orte
+command-settings = einstellungen
+    .description = Konfiguriert den Bot für diesen Server oder dich selbst
+command-settings-region = region
+    .description = Zeigt oder ändert die Region, die Ortssuchen auf diesem Server bevorzugen
+    .region = region
+    .region-description = 'none', Ländercodes wie 'AT, DE' oder ein Rechteck wie '55.0, 5.3; 45.8, 17.4'
+command-settings-language = sprache
+    .description = Zeigt oder ändert die Sprache, in der der Bot mit dir spricht
+    .language = sprache
+    .language-description = Eine Sprache wie 'de' oder 'en', oder 'none' für deine Discord-Sprache
+command-settings-server-language = server-sprache
+    .description = Zeigt oder ändert die Sprache für Mitglieder dieses Servers, die keine gewählt haben
+    .language = sprache
+    .language-description = Eine Sprache wie 'de' oder 'en', oder 'none' für die Standardsprache
+command-time = uhrzeit
+    .description = Zeigt die aktuelle Uhrzeit an einem Ort
+    .clock_changes = zeitumstellungen
+    .clock_changes-description = Zeigt auch, wann die Uhren zuletzt und als nächstes umgestellt werden
+    .place = ort
+    .place-description = Name eines Ortes, Koordinaten, ein Plus Code oder einer deiner gespeicherten Orte
+command-timestamp = zeitstempel
+    .description = Erstellt Discord-Zeitstempel für eine Uhrzeit an einem Ort
+    .time = uhrzeit
+    .time-description = Uhrzeit, z.B. 19:00 oder 7pm
+    .date = datum
+    .date-description = Datum, z.B. 24.12.2024 oder Freitag, standardmäßig heute
+    .place = ort
+    .place-description = Ort, auf den sich die Uhrzeit bezieht, standardmäßig dein Zuhause
+command-convert = umrechnen
+    .description = Rechnet zwischen den Zeitzonen von Orten um
+command-convert-time = uhrzeit
+    .description = Rechnet eine Uhrzeit an einem Ort in die Ortszeit anderer Orte um
+    .time = uhrzeit
+    .time-description = Umzurechnende Uhrzeit, z.B. 15:30 oder 3pm
+    .from = von
+    .from-description = Ort, auf den sich die Uhrzeit bezieht
+    .to = nach
+    .to-description = Ort, in dessen Zeit umgerechnet wird
+    .to_2 = nach_2
+    .to_2-description = Ein weiterer Ort, in dessen Zeit umgerechnet wird
+    .to_3 = nach_3
+    .to_3-description = Ein weiterer Ort, in dessen Zeit umgerechnet wird
+    .to_4 = nach_4
+    .to_4-description = Ein weiterer Ort, in dessen Zeit umgerechnet wird
+    .date = datum
+    .date-description = Datum der Uhrzeit, standardmäßig heute am ersten Ort
+command-meeting = besprechung
+    .description = Findet Zeiten innerhalb der Arbeitszeit an allen angegebenen Orten
+    .place_1 = ort_1
+    .place_1-description = Erster Ort eines Teilnehmers
+    .place_2 = ort_2
+    .place_2-description = Zweiter Ort eines Teilnehmers
+    .place_3 = ort_3
+    .place_3-description = Ein weiterer Ort eines Teilnehmers
+    .place_4 = ort_4
+    .place_4-description = Ein weiterer Ort eines Teilnehmers
+    .date = datum
+    .date-description = Tag der Besprechung am ersten Ort, standardmäßig heute
+    .start = beginn
+    .start-description = Beginn der Arbeitszeit, standardmäßig 9:00
+    .end = ende
+    .end-description = Ende der Arbeitszeit, standardmäßig 17:00
+command-worldclock = weltuhr
+    .description = Postet eine Uhr, die die aktuelle Uhrzeit an mehreren Orten zeigt
+command-worldclock-post = posten
+    .description = Postet eine Weltuhr in diesem Kanal
+    .places = orte
+    .places-description = Durch Strichpunkte getrennte Orte, z.B. Wien; New York; Tokio
+command-worldclock-remove = entfernen
+    .description = Beendet die Aktualisierung einer Weltuhr und löscht sie
+    .message = nachricht
+    .message-description = Link oder ID der Weltuhr-Nachricht
+
 # error messages
 unknown-command = Dieses Kommando kenne ich leider nicht.
-unknown-error = Oje, da ist etwas schiefgelaufen.
\ No newline at end of file
+unknown-error = Oje, da ist etwas schiefgelaufen.
+timestamp-parse-error = Dieses Datum oder diese Uhrzeit verstehe ich nicht: { $error }
+api-busy = Gerade erreichen mich sehr viele Anfragen, bitte versuch es in ein paar Sekunden nochmal.
+
+help-footer =
+    Schreib '!help kommando' für mehr Infos zu einem bestimmten Kommando.
+    Du kannst deine Nachricht an den Bot bearbeiten, dann bearbeitet der Bot auch seine Antwort.
+
+age-timestamp = <t:{ $unix_time }:f>
+age-account-created-at = Das Konto von { $username } wurde am { $timestamp } erstellt
+
+place-selection-timeout = Die Auswahl des Ortes ist abgelaufen
+place-selection-placeholder = Ort auswählen
+place-selection-which-one = Welchen dieser Orte suchst du?
+place-choose-different = Anderen Ort wählen
+place-not-found = Ich konnte keinen passenden Ort für `{ $search_term }` finden
+place-with-elevation = { $place } ({ NUMBER($elevation) } m)
+place-missing-no-home = Bitte nenne mir einen Ort oder speichere dein Zuhause mit `/places add { $home } <ort>`
+
+place-info-population = Einwohner
+place-info-population-amount = { NUMBER($population) }
+place-info-population-dated = { NUMBER($population) } ({ $date })
+place-info-capital = Hauptstadt
+place-info-capital-country = des Landes
+place-info-capital-state = des Bundeslandes
+place-info-capital-region = der Region
+place-info-elevation = Seehöhe
+place-info-timezone = Zeitzone
+place-info-timezone-offset = { $timezone } (UTC{ $offset })
+place-info-coordinates = Koordinaten
+place-info-links = Links
+place-info-website = Webseite
+place-info-names = Andere Namen
+
+format-time = %H:%M
+
+time-current =
+    Es ist **{ $time }** am { DATE($date) } in **{ $place }**
+    Zeitzone: { $timezone } ({ $abbreviation }, UTC{ $offset })
+time-timezone-unknown = Ich konnte die Zeitzone von **{ $place }** nicht bestimmen
+timezone-fallback-nearest-land = _Der Ort liegt in keiner Zeitzone, daher wurde die nächstgelegene ({ $timezone }) verwendet._
+timezone-fallback-country = _Die genaue Zeitzone des Ortes ist unbekannt, daher wurde die übliche seines Landes ({ $timezone }) verwendet._
+timezone-fallback-longitude = _Die Zeitzone des Ortes ist unbekannt, daher wurde sie anhand des Längengrads geschätzt ({ $timezone })._
+time-difference-none = Das ist dieselbe Uhrzeit wie bei dir zuhause.
+time-difference-ahead = Das ist { DURATION($difference) } später als bei dir zuhause.
+time-difference-behind = Das ist { DURATION($difference) } früher als bei dir zuhause.
+time-transition-previous = Letzte Zeitumstellung: am { DATE($date) } (<t:{ $unix_time }:R>) wurden die Uhren von { $time_before } { $abbreviation_before } (UTC{ $offset_before }) auf { $time_after } { $abbreviation_after } (UTC{ $offset_after }) { $direction ->
+        [forward] vorgestellt
+       *[back] zurückgestellt
+    }.
+time-transition-next = Nächste Zeitumstellung: am { DATE($date) } (<t:{ $unix_time }:R>) werden die Uhren von { $time_before } { $abbreviation_before } (UTC{ $offset_before }) auf { $time_after } { $abbreviation_after } (UTC{ $offset_after }) { $direction ->
+        [forward] vorgestellt
+       *[back] zurückgestellt
+    }.
+timestamp-success =
+    { $time } am { DATE($date) } ({ $abbreviation }) in **{ $place }** für alle in ihrer eigenen Zeitzone:
+    { $styles }
+timestamp-style = { $style ->
+        [t] Kurze Uhrzeit
+        [T] Lange Uhrzeit
+        [d] Kurzes Datum
+        [D] Langes Datum
+        [f] Kurzes Datum mit Uhrzeit
+        [F] Langes Datum mit Uhrzeit
+       *[R] Relativ
+    }: { $code } → { $preview }
+time-transition-none = Die Uhren in { $timezone } wurden im letzten Jahr weder umgestellt, noch werden sie im nächsten Jahr umgestellt.
+
+convert-time-entry = **{ $time }** am { DATE($date) } in **{ $place }** ({ $abbreviation })
+convert-time-success =
+    { $source } ist
+    { $targets }
+    Für alle anderen ist das <t:{ $unix_time }:F>
+
+meeting-success =
+    Arbeitszeiten am { DATE($date) }, • markiert die Arbeitszeiten jedes Ortes:
+    { $grid }
+    { $overlaps }
+meeting-overlap = Alle sind in der Arbeit { $ranges }
+meeting-overlap-range = <t:{ $start }:t> – <t:{ $end }:t>
+meeting-no-overlap = An diesem Tag gibt es keine Stunde, die in den Arbeitszeiten aller liegt.
+meeting-clock-change = Achtung, an diesem Tag werden die Uhren in { $places } umgestellt.
+meeting-invalid-hours = Die Arbeitszeiten müssen beginnen, bevor sie enden, aber { $start } liegt nicht vor { $end }.
+
+dst-gap-note = _{ $requested } gab es an diesem Tag nicht, da die Uhren vorgestellt wurden. Stattdessen wird { $shifted } ({ $abbreviation }) angezeigt._
+dst-ambiguous-note = _{ $time } gab es an diesem Tag zweimal, da die Uhren zurückgestellt wurden. Angezeigt wird das erste Mal ({ $abbreviation })._
+dst-ambiguous-other = _Das ist das zweite Mal { $time } ({ $abbreviation }) an diesem Tag._
+dst-show-other = Zweites Mal { $time } ({ $abbreviation }) anzeigen
+
+last-updated = zuletzt aktualisiert: <t:{ $unix_time }:R>
+temperature-current-success = Die aktuelle Temperatur in **{ $place }** beträgt **`{ TEMPERATURE($celsius) }`** _({ $last_updated })_
+
+# backslash before '-' is needed to escape the minus, otherwise discord sees it as an <ul>
+response-invoked-by =
+    { $message }
+    \- angefordert von { $user_mention}
+
+places-saved = **{ $place }** wurde als `{ $alias }` gespeichert
+places-removed = Dein gespeicherter Ort `{ $alias }` wurde entfernt
+places-not-saved = Du hast keinen Ort als `{ $alias }` gespeichert
+places-none-saved = Du hast noch keine Orte gespeichert. Mit `/places add` kannst du einen speichern.
+places-limit-reached = Du kannst höchstens { $limit } Orte speichern. Entferne zuerst einen mit `/places remove`.
+places-list =
+    Deine gespeicherten Orte:
+    { $entries }
+places-list-entry = \- `{ $alias }`: { $place }
+
+world-clock =
+    🕒 **Weltuhr**
+    { $clocks }
+    _Aktualisiert <t:{ $unix_time }:R>_
+world-clock-entry = { $indicator } **{ $place }**: { $time } { $abbreviation }, { DATE($date) }
+world-clock-invalid-count = Bitte nenne zwischen 1 und { $max } Orten, getrennt durch Strichpunkte.
+world-clock-posted = Die Weltuhr wurde gepostet und wird laufend aktualisiert: { $link }
+world-clock-removed = Die Weltuhr wurde entfernt.
+world-clock-not-found = Auf diesem Server gibt es keine Weltuhr mit der Nachricht `{ $input }`.
+language-current = Ich spreche mit dir in `{ $language }`.
+language-updated = Ab jetzt spreche ich mit dir in `{ $language }`.
+language-reset = Ab jetzt spreche ich mit dir wieder in deiner Discord-Sprache.
+language-unsupported = `{ $input }` spreche ich noch nicht. Verfügbare Sprachen sind { $available }.
+server-language-current = Mit Mitgliedern dieses Servers, die keine Sprache gewählt haben, spreche ich in `{ $language }`.
+server-language-updated = Ab jetzt spreche ich mit Mitgliedern dieses Servers, die keine Sprache gewählt haben, in `{ $language }`.
+region-current = Ortssuchen auf diesem Server bevorzugen die Region `{ $region }`
+region-updated = Ortssuchen auf diesem Server bevorzugen jetzt die Region `{ $region }`
+region-unknown-countries = Es gibt keine Länder mit den Codes { $codes }. Verwende ISO-3166-1-Alpha-2-Codes wie `AT, DE`.
+region-invalid =
+    `{ $input }` ist keine gültige Region. Verwende `none`, eine Liste von Ländercodes wie `AT, DE`
+    oder zwei gegenüberliegende Ecken eines Rechtecks wie `55.0, 5.3; 45.8, 17.4`.
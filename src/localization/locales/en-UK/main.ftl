@@ -1,6 +1,8 @@
 # error messages
 unknown-command = Sorry, I don't know this command.
 unknown-error = Hold up, something went wrong.
+timestamp-parse-error = I don't understand this date or time: { $error }
+api-busy = I'm getting a lot of requests right now, please try again in a few seconds.
 
 help-footer =
     Type '!help command' for more info on a specific command.
@@ -12,13 +14,117 @@ age-account-created-at = { $username }'s account was created at { $timestamp }
 place-selection-timeout = Place selection has timed out
 place-selection-placeholder = Select place
 place-selection-which-one = Which one of these is the place you are looking for?
+place-choose-different = Choose a different place
 place-not-found = Could not find a matching place for `{ $search_term }`
+place-with-elevation = { $place } ({ $elevation } m)
+place-missing-no-home = Please tell me a place or save your home with `/places add { $home } <place>`
+
+place-info-population = Population
+place-info-population-amount = { NUMBER($population) }
+place-info-population-dated = { NUMBER($population) } ({ $date })
+place-info-capital = Capital
+place-info-capital-country = of the country
+place-info-capital-state = of the state
+place-info-capital-region = of the region
+place-info-elevation = Elevation
+place-info-timezone = Timezone
+place-info-timezone-offset = { $timezone } (UTC{ $offset })
+place-info-coordinates = Coordinates
+place-info-links = Links
+place-info-website = Website
+place-info-names = Other names
+
+format-time = %H:%M
+
+time-current =
+    It is **{ $time }** on { DATE($date) } in **{ $place }**
+    Timezone: { $timezone } ({ $abbreviation }, UTC{ $offset })
+time-timezone-unknown = I could not determine the timezone of **{ $place }**
+timezone-fallback-nearest-land = _The place is not within any timezone, so the nearest one ({ $timezone }) was used._
+timezone-fallback-country = _The exact timezone of the place is unknown, so the usual one of its country ({ $timezone }) was used._
+timezone-fallback-longitude = _The timezone of the place is unknown, so it was estimated from the longitude ({ $timezone })._
+time-difference-none = That is the same time as at your home.
+time-difference-ahead = That is { DURATION($difference) } ahead of your home.
+time-difference-behind = That is { DURATION($difference) } behind your home.
+time-transition-previous = Last clock change: on { DATE($date) } (<t:{ $unix_time }:R>), the clocks were turned { $direction ->
+        [forward] forward
+       *[back] back
+    } from { $time_before } { $abbreviation_before } (UTC{ $offset_before }) to { $time_after } { $abbreviation_after } (UTC{ $offset_after }).
+time-transition-next = Next clock change: on { DATE($date) } (<t:{ $unix_time }:R>), the clocks will be turned { $direction ->
+        [forward] forward
+       *[back] back
+    } from { $time_before } { $abbreviation_before } (UTC{ $offset_before }) to { $time_after } { $abbreviation_after } (UTC{ $offset_after }).
+timestamp-success =
+    { $time } on { DATE($date) } ({ $abbreviation }) in **{ $place }** for everyone in their own timezone:
+    { $styles }
+timestamp-style = { $style ->
+        [t] Short time
+        [T] Long time
+        [d] Short date
+        [D] Long date
+        [f] Short date and time
+        [F] Long date and time
+       *[R] Relative
+    }: { $code } → { $preview }
+time-transition-none = The clocks in { $timezone } neither changed during the last year nor will they change during the next one.
+
+convert-time-entry = **{ $time }** on { DATE($date) } in **{ $place }** ({ $abbreviation })
+convert-time-success =
+    { $source } is
+    { $targets }
+    For everyone else, that is <t:{ $unix_time }:F>
+
+meeting-success =
+    Working hours on { DATE($date) }, • marks the working hours of each place:
+    { $grid }
+    { $overlaps }
+meeting-overlap = Everyone is at work { $ranges }
+meeting-overlap-range = <t:{ $start }:t> – <t:{ $end }:t>
+meeting-no-overlap = There is no hour within everyone's working hours on that day.
+meeting-clock-change = Note that the clocks change on that day in { $places }.
+meeting-invalid-hours = The working hours must start before they end, but { $start } is not before { $end }.
+
+dst-gap-note = _{ $requested } did not exist on that day, since the clocks were turned forward. Showing { $shifted } ({ $abbreviation }) instead._
+dst-ambiguous-note = _{ $time } occurred twice on that day, since the clocks were turned back. Showing the first one ({ $abbreviation })._
+dst-ambiguous-other = _This is the second { $time } ({ $abbreviation }) of that day._
+dst-show-other = Show the second { $time } ({ $abbreviation })
 
 last-updated = last updated: <t:{ $unix_time }:R>
-temperature-current-success = The current temperature in **{ $place }** is **`{ $celcius }°C`** _({ $last_updated })_
+temperature-current-success = The current temperature in **{ $place }** is **`{ TEMPERATURE($celsius) }`** _({ $last_updated })_
 
 # backslash before '-' is needed to escape the minus, otherwise discord sees it as an <ul>
 response-invoked-by =
     { $message }
     \- invoked by { $user_mention}
 
+places-saved = Saved **{ $place }** as `{ $alias }`
+places-removed = Removed your saved place `{ $alias }`
+places-not-saved = You have no place saved as `{ $alias }`
+places-none-saved = You have not saved any places yet. Use `/places add` to save one.
+places-limit-reached = You can save at most { $limit } places. Remove one with `/places remove` first.
+places-list =
+    Your saved places:
+    { $entries }
+places-list-entry = \- `{ $alias }`: { $place }
+
+world-clock =
+    🕒 **World clock**
+    { $clocks }
+    _Updated <t:{ $unix_time }:R>_
+world-clock-entry = { $indicator } **{ $place }**: { $time } { $abbreviation }, { DATE($date) }
+world-clock-invalid-count = Please name between 1 and { $max } places, separated by semicolons.
+world-clock-posted = The world clock was posted and will be kept up to date: { $link }
+world-clock-removed = The world clock was removed.
+world-clock-not-found = There is no world clock on this server with the message `{ $input }`.
+language-current = I am talking to you in `{ $language }`.
+language-updated = From now on, I will talk to you in `{ $language }`.
+language-reset = From now on, I will talk to you in your Discord language again.
+language-unsupported = I cannot speak `{ $input }` yet. Available languages are { $available }.
+server-language-current = Members of this server who have not chosen a language are talked to in `{ $language }`.
+server-language-updated = From now on, members of this server who have not chosen a language are talked to in `{ $language }`.
+region-current = Place searches on this server prefer the region `{ $region }`
+region-updated = Place searches on this server now prefer the region `{ $region }`
+region-unknown-countries = There are no countries with the codes { $codes }. Use ISO 3166-1 alpha-2 codes like `AT, DE`.
+region-invalid =
+    `{ $input }` is not a valid region. Use `none`, a list of country codes like `AT, DE`
+    or two opposite corners of a box like `55.0, 5.3; 45.8, 17.4`.
@@ -1,36 +1,221 @@
+pub mod formatting;
+
 pub use fluent_templates::Loader;
+use fluent_templates::fluent_bundle::types::FluentNumber;
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::LanguageIdentifier;
 
+/// Looks up a message in the language of the first argument, which is either the invocation
+/// context or a [LanguageIdentifier], see [LanguageSource].
 macro_rules! localize {
     // Case 1: No arguments provided
-    ( $text_id:expr ) => {{
-        $crate::LOCALES.lookup(&crate::USER_LANG, $text_id)
+    ( $language:expr, $text_id:expr ) => {{
+        let language = $crate::localization::LanguageSource::ui_language(&$language);
+        $crate::LOCALES.lookup(&language, $text_id)
     }};
 
     // Case 2: One or more arguments provided
-    ( $text_id:expr, $( $arg_name:ident: $arg_value:expr ),* $(,)? ) => {{
+    ( $language:expr, $text_id:expr, $( $arg_name:ident: $arg_value:expr ),* $(,)? ) => {{
+        let language = $crate::localization::LanguageSource::ui_language(&$language);
         let args = std::collections::HashMap::from_iter([
-            $(  // key-value pair (Cow<str>, FluentValue::String)
-                (std::borrow::Cow::from(stringify!($arg_name)),
-                fluent_templates::fluent_bundle::FluentValue::String($arg_value.to_string().into()))
+            $(  // key-value pair (Cow<str>, FluentValue), numbers stay numbers for plurals and formatting
+                (std::borrow::Cow::from(stringify!($arg_name)), {
+                    #[allow(unused_imports)]
+                    use $crate::localization::{NumberArgument, TextArgument};
+                    (&$crate::localization::Argument(&$arg_value)).to_fluent()
+                })
             ),*
         ]);
-        $crate::LOCALES.lookup_with_args(&crate::USER_LANG, $text_id, &args)
+        $crate::LOCALES.lookup_with_args(&language, $text_id, &args)
     }};
 }
 
+/// Like `localize!`, but without the Unicode isolation marks Fluent puts around arguments.
 macro_rules! localize_raw {
     // Case 1: No arguments provided
-    ( $text_id:expr ) => {{
-        let output = localize!($text_id);
+    ( $language:expr, $text_id:expr ) => {{
+        let output = localize!($language, $text_id);
         output.replace("\u{2068}", "").replace("\u{2069}", "")
     }};
 
     // Case 2: One or more arguments provided
-    ( $text_id:expr, $( $arg_name:ident: $arg_value:expr ),* $(,)? ) => {{
-        let output = localize!($text_id, $( $arg_name: $arg_value ),*);
+    ( $language:expr, $text_id:expr, $( $arg_name:ident: $arg_value:expr ),* $(,)? ) => {{
+        let output = localize!($language, $text_id, $( $arg_name: $arg_value ),*);
         output.replace("\u{2068}", "").replace("\u{2069}", "")
     }};
 }
 
 pub(crate) use localize;
-pub(crate) use localize_raw;
\ No newline at end of file
+pub(crate) use localize_raw;
+
+/// An argument of a localized message, which is passed to Fluent as a number if it is one and as
+/// text otherwise. The trait is picked by method resolution, which prefers [NumberArgument] since it
+/// is implemented for the argument itself rather than a reference to it.
+#[doc(hidden)]
+pub struct Argument<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait NumberArgument {
+    fn to_fluent(&self) -> FluentValue<'static>;
+}
+macro_rules! number_argument {
+    ( $( $number:ty ),* ) => {
+        $(
+            impl NumberArgument for Argument<'_, $number> {
+                fn to_fluent(&self) -> FluentValue<'static> {
+                    FluentValue::Number(FluentNumber::new(*self.0 as f64, formatting::number_options()))
+                }
+            }
+        )*
+    };
+}
+number_argument!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+#[doc(hidden)]
+pub trait TextArgument {
+    fn to_fluent(&self) -> FluentValue<'static>;
+}
+impl<T: std::fmt::Display + ?Sized> TextArgument for &Argument<'_, T> {
+    fn to_fluent(&self) -> FluentValue<'static> {
+        FluentValue::String(self.0.to_string().into())
+    }
+}
+
+/// Anything messages can be localized for, i.e. a command invocation or an explicit language.
+pub trait LanguageSource {
+    fn ui_language(&self) -> LanguageIdentifier;
+}
+impl LanguageSource for LanguageIdentifier {
+    fn ui_language(&self) -> LanguageIdentifier {
+        self.clone()
+    }
+}
+impl LanguageSource for &LanguageIdentifier {
+    fn ui_language(&self) -> LanguageIdentifier {
+        (*self).clone()
+    }
+}
+impl LanguageSource for crate::Context<'_> {
+    fn ui_language(&self) -> LanguageIdentifier {
+        user_language(*self)
+    }
+}
+
+/// Returns the language the bot talks to the invoking user in. In order of precedence, that is the
+/// language the user chose with `/settings language`, their Discord locale for slash commands if the
+/// bot speaks it, the default language of the guild and finally the fallback language.
+pub fn user_language(ctx: crate::Context<'_>) -> LanguageIdentifier {
+    chosen_language(ctx)
+        .or_else(|| ctx.locale().and_then(supported_language))
+        .unwrap_or_else(|| guild_language(ctx.data(), ctx.guild_id()))
+}
+
+/// Returns the default language of the guild, or the fallback language outside of guilds and in
+/// guilds without a configured language.
+pub fn guild_language(data: &crate::ApplicationState, guild_id: Option<poise::serenity_prelude::GuildId>)
+    -> LanguageIdentifier
+{
+    guild_id
+        .and_then(|id| data.guild_settings.get_with(&id.get(), |settings| settings.language.clone()))
+        .flatten()
+        .and_then(|language| language.parse().ok())
+        .unwrap_or(crate::FALLBACK_LANGUAGE)
+}
+
+/// Returns the language place names and addresses should be shown in for the invoking user, i.e. their
+/// chosen language or Discord locale and the configured query language otherwise.
+pub fn query_language(ctx: crate::Context<'_>) -> LanguageIdentifier {
+    chosen_language(ctx)
+        .or_else(|| ctx.locale().and_then(|locale| locale.parse().ok()))
+        .unwrap_or_else(|| crate::QUERY_LANG.clone())
+}
+
+/// Returns the available locale matching the requested `language`, e.g. `en-UK` for `en` or `en-US`.
+pub fn supported_language(language: &str) -> Option<LanguageIdentifier> {
+    let requested: LanguageIdentifier = language.trim().parse().ok()?;
+    let locales: Vec<&LanguageIdentifier> = crate::LOCALES.locales().collect();
+
+    locales.iter()
+        .find(|locale| ***locale == requested)
+        .or_else(|| locales.iter().find(|locale| locale.language == requested.language))
+        .map(|locale| (*locale).clone())
+}
+
+// the language the user explicitly chose, which overrides everything else
+fn chosen_language(ctx: crate::Context<'_>) -> Option<LanguageIdentifier> {
+    ctx.data().user_settings.get_with(&ctx.author().id.get(), |settings| settings.language.clone())
+        .flatten()
+        .and_then(|language| language.parse().ok())
+}
+
+// locales Discord supports for command names and descriptions, see
+// https://discord.com/developers/docs/reference#locales
+const DISCORD_LOCALES: [&str; 32] = [
+    "id", "da", "de", "en-GB", "en-US", "es-ES", "es-419", "fr", "hr", "it", "lt", "hu", "nl", "no", "pl", "pt-BR",
+    "ro", "fi", "sv-SE", "vi", "tr", "cs", "el", "bg", "ru", "uk", "hi", "th", "zh-CN", "ja", "zh-TW", "ko",
+];
+
+/// Adds the translated names and descriptions of the `commands`, their subcommands and parameters for
+/// every locale, so Discord shows them in the language of each user.
+///
+/// The Fluent message of a command is named after its full name with spaces replaced by dashes and
+/// prefixed with `command-`, e.g. `command-settings-region`, and its value is the translated name. The attribute `.description` holds the
+/// description, `.<parameter>` and `.<parameter>-description` hold those of the parameters.
+pub fn apply_command_translations(commands: &mut [poise::Command<crate::ApplicationState, crate::Error>]) {
+    for command in commands {
+        apply_translations(command, "");
+    }
+}
+
+fn apply_translations(command: &mut poise::Command<crate::ApplicationState, crate::Error>, parent_id: &str) {
+    let id = match parent_id {
+        "" => format!("command-{}", command.name),
+        parent_id => format!("{}-{}", parent_id, command.name),
+    };
+
+    for locale in crate::LOCALES.locales() {
+        let translate = |attribute: Option<&str>| {
+            let text_id = match attribute {
+                Some(attribute) => format!("{}.{}", id, attribute),
+                None => id.clone(),
+            };
+            crate::LOCALES.lookup_single_language::<&str>(locale, &text_id, None).ok()
+        };
+        let discord_locales = DISCORD_LOCALES.iter()
+            .filter(|discord_locale| discord_locale.parse::<LanguageIdentifier>()
+                .is_ok_and(|discord_locale| discord_locale.language == locale.language));
+
+        for discord_locale in discord_locales {
+            let discord_locale = discord_locale.to_string();
+
+            if let Some(name) = translate(None).filter(|name| is_valid_name(name, &id)) {
+                command.name_localizations.insert(discord_locale.clone(), name);
+            }
+            if let Some(description) = translate(Some("description")) {
+                command.description_localizations.insert(discord_locale.clone(), description);
+            }
+            for parameter in &mut command.parameters {
+                if let Some(name) = translate(Some(&parameter.name)).filter(|name| is_valid_name(name, &id)) {
+                    parameter.name_localizations.insert(discord_locale.clone(), name);
+                }
+                if let Some(description) = translate(Some(&format!("{}-description", parameter.name))) {
+                    parameter.description_localizations.insert(discord_locale.clone(), description);
+                }
+            }
+        }
+    }
+
+    for subcommand in &mut command.subcommands {
+        apply_translations(subcommand, &id);
+    }
+}
+
+// Discord rejects the registration of all commands if a single name is invalid
+fn is_valid_name(name: &str, text_id: &str) -> bool {
+    let valid = (1..=32).contains(&name.chars().count())
+        && name.chars().all(|c| c == '-' || c == '_' || (c.is_alphanumeric() && !c.is_uppercase()));
+    if !valid {
+        log::warn!("Ignoring the translation '{}' of '{}', since it is not a valid command name", name, text_id);
+    }
+    valid
+}
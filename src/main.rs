@@ -3,6 +3,7 @@
 mod commands;
 mod sources;
 mod localization;
+mod storage;
 mod utils;
 
 
@@ -13,19 +14,19 @@ use log::*;
 use poise::{serenity_prelude as serenity, CreateReply, PrefixFrameworkOptions};
 use serenity::GatewayIntents;
 use sources::geo_time;
+use sources::geocoder::Geocoder;
+use sources::nominatim::SearchRegion;
 use std::sync::Arc;
+use storage::settings::{GuildSettings, UserSettings, WorldClock};
+use storage::PersistentMap;
 use std::time::Duration;
 use thiserror::Error;
 
 
-const USER_LANG_ENV: Option<&str> = std::option_env!("USER_LANGUAGE");
 const QUERY_LANG_ENV: Option<&str> = std::option_env!("QUERY_LANGUAGE");
 const FALLBACK_LANGUAGE: LanguageIdentifier = langid!("en-UK");
 
 lazy_static! {
-    static ref USER_LANG: LanguageIdentifier = USER_LANG_ENV.map_or_else(|| FALLBACK_LANGUAGE,
-        |s| s.parse().expect("FATAL ERROR: Malformed fallback language"));
-
     static ref QUERY_LANG: LanguageIdentifier = QUERY_LANG_ENV.map_or_else(|| FALLBACK_LANGUAGE,
         |s| s.parse().expect("FATAL ERROR: Malformed fallback language"));
 }
@@ -37,6 +38,21 @@ type Context<'a> = poise::Context<'a, ApplicationState, Error>;
 #[derive(Debug)]
 struct ApplicationState {
     pub http_client: reqwest::Client,
+    pub geocoder: Geocoder,
+    pub guild_settings: PersistentMap<u64, GuildSettings>,
+    pub user_settings: PersistentMap<u64, UserSettings>,
+    /// world clocks keyed by the id of their message, shared with the task updating them
+    pub world_clocks: Arc<PersistentMap<u64, WorldClock>>,
+}
+impl ApplicationState {
+    /// Returns the region that place searches in the given guild are biased towards.
+    /// Direct messages and guilds without a configured region use the default region.
+    pub fn search_region(&self, guild_id: Option<serenity::GuildId>) -> SearchRegion {
+        guild_id
+            .and_then(|id| self.guild_settings.get(&id.get()))
+            .map(|settings| settings.search_region)
+            .unwrap_or_default()
+    }
 }
 
 // custom top-level error type used throughout the project
@@ -48,6 +64,9 @@ pub enum Error {
     #[error("Error in API request: {0}")]
     ApiError(#[from] sources::common::ApiError),
 
+    #[error("Failed to access persistent storage: {0}")]
+    StorageError(#[from] storage::StorageError),
+
     #[error("Handled error: {inner:?}")]
     Handled {
         inner: Box<dyn std::error::Error + Send + Sync>,
@@ -68,6 +87,11 @@ fluent_templates::static_loader! {
         fallback_language: "en-UK",
         // A fluent resource that is shared with every locale.
         // core_locales: "./locales/core.ftl",
+        // Formats numbers and dates per locale and adds custom functions like TEMPERATURE().
+        customise: |bundle| {
+            bundle.set_formatter(Some(localization::formatting::format_value));
+            localization::formatting::add_functions(bundle);
+        },
     };
 }
 
@@ -76,11 +100,10 @@ fluent_templates::static_loader! {
 async fn main() {
     env_logger::init();
     geo_time::init();
+    #[cfg(feature = "offline-geocoding")]
+    sources::offline_geocoding::init();
 
     // check if querying ENV variables succeeded
-    if USER_LANG_ENV.is_none() {
-        warn!("Failed to parse ENV variable 'USER_LANGUAGE'. Falling back to default language '{}'", FALLBACK_LANGUAGE);
-    }
     if QUERY_LANG_ENV.is_none() {
         warn!("Failed to parse ENV variable 'QUERY_LANGUAGE'. Falling back to default language '{}'", FALLBACK_LANGUAGE);
     }
@@ -95,6 +118,31 @@ async fn main() {
         .build()
         .expect("Failed to create HTTP client for future API requests.");
 
+    let geocoder = Geocoder::from_env();
+
+    let guild_settings = PersistentMap::load("guild_settings")
+        .expect("Failed to load the stored guild settings.");
+    let user_settings = PersistentMap::load("user_settings")
+        .expect("Failed to load the stored user settings.");
+    let world_clocks = Arc::new(PersistentMap::load("world_clocks")
+        .expect("Failed to load the stored world clocks."));
+
+    let mut commands = vec![
+        commands::general::help(),
+        commands::general::age(),
+        commands::climate::temperature(),
+        commands::places::places(),
+        commands::places::place(),
+        commands::settings::settings(),
+        commands::time::time(),
+        commands::time::convert(),
+        commands::time::meeting(),
+        commands::time::timestamp(),
+        commands::world_clock::world_clock(),
+    ];
+    // registered with the translated names and descriptions, which Discord picks from by user locale
+    apply_command_translations(&mut commands);
+
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
             prefix_options: PrefixFrameworkOptions {
@@ -103,21 +151,22 @@ async fn main() {
                 case_insensitive_commands: true,
                 ..Default::default()
             },
-            commands: vec![
-                commands::general::help(),
-                commands::general::age(),
-                commands::climate::temperature(),
-            ],
+            commands,
             on_error: |err| Box::pin(on_error(err)),
             ..Default::default()
         })
         .setup(|ctx, _ready, framework| {
             Box::pin(async move {
                 register_commands(ctx, framework).await?;
+                tokio::spawn(commands::world_clock::keep_updated(ctx.http.clone(), world_clocks.clone()));
 
                 // create shared state object available in every command invocation
                 Ok(ApplicationState {
                     http_client,
+                    geocoder,
+                    guild_settings,
+                    user_settings,
+                    world_clocks,
                 })
             })
         })
@@ -136,16 +185,24 @@ async fn on_error(error: poise::FrameworkError<'_, ApplicationState, Error>) {
     println!("{:#?}", &error);
 
     match error {
-        poise::FrameworkError::Command {ctx, .. } => {
+        poise::FrameworkError::Command {ctx, error, .. } => {
+            let message = match error {
+                // the command already told the user what went wrong
+                Error::Handled { .. } => return,
+                Error::ApiError(sources::common::ApiError::Busy) => localize!(ctx, "api-busy"),
+                _ => localize!(ctx, "unknown-error"),
+            };
+
             let _ = ctx.send(
                 CreateReply::default()
-                    .content(localize!("unknown-error"))
+                    .content(message)
                     .reply(true)
                     .ephemeral(true)
             ).await;
         },
-        poise::FrameworkError::UnknownCommand {msg, ctx, .. } => {
-            let _ = msg.reply(&ctx.http, localize!("unknown-command")).await;
+        poise::FrameworkError::UnknownCommand {msg, ctx, framework, .. } => {
+            let language = guild_language(framework.user_data, msg.guild_id);
+            let _ = msg.reply(&ctx.http, localize!(language, "unknown-command")).await;
         },
         // use defaults for all other error types
         _ => {
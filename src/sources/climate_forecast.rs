@@ -19,21 +19,26 @@ impl From<CurrentTempResult> for SingleTemperature {
 
 
 #[cached(
-    ty = "TimedCache<Coordinates, SingleTemperature>",
+    ty = "TimedCache<(Coordinates, Option<i32>), SingleTemperature>",
     create = "{ TimedCache::with_lifespan(CACHE_TTL_SECONDS) }",
-    convert = r#"{ location.clone() }"#,
+    convert = r#"{ (location.clone(), elevation.map(|e| e.round() as i32)) }"#,
     result = true
 )]
-pub async fn get_current_temperature(client: &reqwest::Client, location: &Coordinates)
+pub async fn get_current_temperature(client: &reqwest::Client, location: &Coordinates, elevation: Option<f32>)
                                      -> Result<SingleTemperature, ApiError>
 {
-    let params = [
+    let mut params = vec![
         ("latitude", location.latitude.to_string()),
         ("longitude", location.longitude.to_string()),
         ("current", "temperature_2m".to_string()),
         ("timeformat", "unixtime".to_string()),
     ];
 
+    // without an explicit elevation, the API uses the terrain height of its ~90m grid cell
+    if let Some(elevation) = elevation {
+        params.push(("elevation", elevation.to_string()));
+    }
+
     common::query_api::<SingleTemperature, CurrentTempResult, ClimateApiError>
         (client, BASE_URL, params).await
 }
\ No newline at end of file
@@ -104,14 +104,15 @@ impl FromIterator<TemperatureDataPoint> for BTreeMap<i64, TemperatureDataPoint>
 
 // ----------------------- Public Functions --------------------------
 
-pub async fn get_past_temperature(client: &reqwest::Client, location: &Coordinates, timestamp: &DateTime<Tz>)
+pub async fn get_past_temperature(client: &reqwest::Client, location: &Coordinates, elevation: Option<f32>,
+                                  timestamp: &DateTime<Tz>)
                                   -> Result<SingleTemperature, ApiError>
 {
     // round the timestamp to the nearest hour, since the API only stores temperature data every hour
     let rounded_timestamp = timestamp.duration_round(TimeDelta::hours(1))
         .map_err(|_| ApiError::BadRequest { reason: "Rounding the timestamp exceeded its possible value space".to_string()})?;
     
-    let temperature_series = get_temperature_series(client, location, &rounded_timestamp, &rounded_timestamp).await?;
+    let temperature_series = get_temperature_series(client, location, elevation, &rounded_timestamp, &rounded_timestamp).await?;
 
     let data = temperature_series.get(&rounded_timestamp.timestamp());
 
@@ -125,6 +126,8 @@ pub async fn get_past_temperature(client: &reqwest::Client, location: &Coordinat
 /// `location` between the `start_time` and `end_time`.
 /// # Arguments
 /// - `location` - the geographic coordinates of the location of interest
+/// - `elevation` - the elevation in metres the temperature should be downscaled to. Defaults to the
+///   terrain height of the location if `None`.
 /// - `start_time` - the start time of the data series (inclusive)
 /// - `end_time` - the end time of the query (inclusive)
 ///
@@ -139,7 +142,7 @@ pub async fn get_past_temperature(client: &reqwest::Client, location: &Coordinat
 /// - [ApiError::Parsing](ApiError::Parsing) if the API response contained unexpected date and could
 ///   thus not be parsed.
 /// ```
-pub async fn get_temperature_series(client: &reqwest::Client, location: &Coordinates,
+pub async fn get_temperature_series(client: &reqwest::Client, location: &Coordinates, elevation: Option<f32>,
                                     start_time: &DateTime<Tz>, end_time: &DateTime<Tz>)
                                     -> Result<BTreeMap<i64, TemperatureDataPoint>, ApiError>
 {
@@ -164,7 +167,7 @@ pub async fn get_temperature_series(client: &reqwest::Client, location: &Coordin
 
 
     // execute the request
-    let params = [
+    let mut params = vec![
         ("latitude", location.latitude.to_string()),
         ("longitude", location.longitude.to_string()),
         ("hourly", "temperature_2m".to_string()),
@@ -174,6 +177,10 @@ pub async fn get_temperature_series(client: &reqwest::Client, location: &Coordin
         ("timeformat", "unixtime".to_string()),
     ];
 
+    if let Some(elevation) = elevation {
+        params.push(("elevation", elevation.to_string()));
+    }
+
     let temperature_series =
         common::query_api::<BTreeMap<i64, TemperatureDataPoint>, HistoricalTemperature, ClimateApiError>
         (client, BASE_URL, params).await;
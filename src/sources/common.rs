@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
 use std::hash::Hash;
 use thiserror::Error;
 
@@ -20,6 +21,9 @@ pub enum ApiError {
     BadRequest {
         reason: String
     },
+
+    #[error("Too many requests are waiting for the API")]
+    Busy,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -32,7 +36,7 @@ impl From<ClimateApiError> for ApiError {
     }
 }
 
-#[derive(Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Coordinates {
     pub latitude: f64,
     pub longitude: f64
@@ -41,6 +45,18 @@ impl Coordinates {
     pub fn new(latitude: f64, longitude: f64) -> Coordinates {
         Coordinates {latitude, longitude}
     }
+
+    /// Returns the great-circle distance to the `other` coordinates in kilometres.
+    pub fn distance_km(&self, other: &Coordinates) -> f64 {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+
+        let (lat_a, lat_b) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let delta_lat = lat_b - lat_a;
+        let delta_lon = (other.longitude - self.longitude).to_radians();
+
+        let a = (delta_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (delta_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
 }
 impl PartialEq for Coordinates {
     fn eq(&self, other: &Self) -> bool {
@@ -48,6 +64,14 @@ impl PartialEq for Coordinates {
             self.longitude.thousandths() == other.longitude.thousandths()
     }
 }
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let north_south = if self.latitude < 0.0 { 'S' } else { 'N' };
+        let east_west = if self.longitude < 0.0 { 'W' } else { 'E' };
+
+        write!(f, "{:.4}°{} {:.4}°{}", self.latitude.abs(), north_south, self.longitude.abs(), east_west)
+    }
+}
 impl Eq for Coordinates {} // marker interface to guarantee that PartialEq implementation is reflexive
 impl Hash for Coordinates {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
@@ -101,6 +125,16 @@ where
 }
 
 
+/// Deserializes an explicit `null` the same way as a missing field, i.e. to the type's default value.
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+
 pub fn truncate_utf8(s: &mut String, max_chars: usize) {
     match s.char_indices().nth(max_chars) {
         None => (),
@@ -1,4 +1,5 @@
 use super::common::Coordinates;
+use chrono::{DateTime, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta, TimeZone, Utc};
 pub use chrono_tz::Tz;
 use std::sync::OnceLock;
 use tzf_rs::gen::Timezones;
@@ -6,6 +7,31 @@ use tzf_rs::Finder;
 
 static FINDER: OnceLock<Finder> = OnceLock::new();
 
+// the timezone of the capital or the most populous region of frequently searched countries, other
+// countries are left to the estimate from the longitude
+const COUNTRY_TIMEZONES: [(&str, Tz); 80] = [
+    ("ad", Tz::Europe__Andorra), ("ae", Tz::Asia__Dubai), ("al", Tz::Europe__Tirane), ("ar", Tz::America__Argentina__Buenos_Aires),
+    ("at", Tz::Europe__Vienna), ("au", Tz::Australia__Sydney), ("ba", Tz::Europe__Sarajevo), ("bd", Tz::Asia__Dhaka),
+    ("be", Tz::Europe__Brussels), ("bg", Tz::Europe__Sofia), ("br", Tz::America__Sao_Paulo), ("by", Tz::Europe__Minsk),
+    ("ca", Tz::America__Toronto), ("ch", Tz::Europe__Zurich), ("cl", Tz::America__Santiago), ("cn", Tz::Asia__Shanghai),
+    ("co", Tz::America__Bogota), ("cy", Tz::Asia__Nicosia), ("cz", Tz::Europe__Prague), ("de", Tz::Europe__Berlin),
+    ("dk", Tz::Europe__Copenhagen), ("dz", Tz::Africa__Algiers), ("ee", Tz::Europe__Tallinn), ("eg", Tz::Africa__Cairo),
+    ("es", Tz::Europe__Madrid), ("et", Tz::Africa__Addis_Ababa), ("fi", Tz::Europe__Helsinki), ("fr", Tz::Europe__Paris),
+    ("gb", Tz::Europe__London), ("gr", Tz::Europe__Athens), ("hr", Tz::Europe__Zagreb), ("hu", Tz::Europe__Budapest),
+    ("id", Tz::Asia__Jakarta), ("ie", Tz::Europe__Dublin), ("il", Tz::Asia__Jerusalem), ("in", Tz::Asia__Kolkata),
+    ("iq", Tz::Asia__Baghdad), ("ir", Tz::Asia__Tehran), ("is", Tz::Atlantic__Reykjavik), ("it", Tz::Europe__Rome),
+    ("jp", Tz::Asia__Tokyo), ("ke", Tz::Africa__Nairobi), ("kr", Tz::Asia__Seoul), ("kz", Tz::Asia__Almaty),
+    ("li", Tz::Europe__Vaduz), ("lt", Tz::Europe__Vilnius), ("lu", Tz::Europe__Luxembourg), ("lv", Tz::Europe__Riga),
+    ("ma", Tz::Africa__Casablanca), ("mc", Tz::Europe__Monaco), ("md", Tz::Europe__Chisinau), ("me", Tz::Europe__Podgorica),
+    ("mk", Tz::Europe__Skopje), ("mt", Tz::Europe__Malta), ("mx", Tz::America__Mexico_City), ("my", Tz::Asia__Kuala_Lumpur),
+    ("ng", Tz::Africa__Lagos), ("nl", Tz::Europe__Amsterdam), ("no", Tz::Europe__Oslo), ("nz", Tz::Pacific__Auckland),
+    ("pe", Tz::America__Lima), ("ph", Tz::Asia__Manila), ("pk", Tz::Asia__Karachi), ("pl", Tz::Europe__Warsaw),
+    ("pt", Tz::Europe__Lisbon), ("ro", Tz::Europe__Bucharest), ("rs", Tz::Europe__Belgrade), ("ru", Tz::Europe__Moscow),
+    ("sa", Tz::Asia__Riyadh), ("se", Tz::Europe__Stockholm), ("sg", Tz::Asia__Singapore), ("si", Tz::Europe__Ljubljana),
+    ("sk", Tz::Europe__Bratislava), ("sm", Tz::Europe__San_Marino), ("th", Tz::Asia__Bangkok), ("tr", Tz::Europe__Istanbul),
+    ("ua", Tz::Europe__Kyiv), ("us", Tz::America__New_York), ("vn", Tz::Asia__Ho_Chi_Minh), ("za", Tz::Africa__Johannesburg),
+];
+
 fn get_finder() -> &'static Finder {
     FINDER.get_or_init(|| {
         // Hard-coded relative path is fine here, since this will be evaluated at compile time and thus
@@ -25,4 +51,336 @@ pub fn get_timezone_name(coordinates: &Coordinates) -> String {
 
 pub fn get_timezone(coordinates: &Coordinates) -> Option<Tz> {
     get_timezone_name(coordinates).parse().ok()
-}
\ No newline at end of file
+}
+
+/// How the timezone of a location was determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimezoneSource {
+    /// the location lies within the boundaries of the timezone
+    Exact,
+    /// the location lies outside of all timezone boundaries (e.g. at sea), so the closest one was taken
+    NearestLand,
+    /// the usual timezone of the country the location belongs to
+    Country,
+    /// a fixed offset of one hour per 15° of longitude
+    Longitude,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvedTimezone {
+    pub timezone: Tz,
+    pub source: TimezoneSource,
+}
+
+/// Determines the timezone at the `coordinates`, falling back to the timezone of the nearest land, the
+/// usual timezone of the country with the given ISO 3166-1 alpha-2 `country_code` and finally an
+/// offset derived from the longitude, so there always is a result.
+pub fn resolve_timezone(coordinates: &Coordinates, country_code: Option<&str>) -> ResolvedTimezone {
+    resolve_timezone_with(coordinates, country_code, get_timezone)
+}
+
+// like `resolve_timezone`, but with the lookup of the timezone boundaries passed in
+fn resolve_timezone_with(coordinates: &Coordinates, country_code: Option<&str>,
+                         lookup: impl Fn(&Coordinates) -> Option<Tz>) -> ResolvedTimezone
+{
+    let resolved = |timezone, source| ResolvedTimezone { timezone, source };
+
+    if let Some(timezone) = lookup(coordinates) {
+        return resolved(timezone, TimezoneSource::Exact);
+    }
+    if let Some(timezone) = get_nearest_timezone(coordinates, &lookup) {
+        return resolved(timezone, TimezoneSource::NearestLand);
+    }
+    if let Some(timezone) = country_code.and_then(get_country_timezone) {
+        return resolved(timezone, TimezoneSource::Country);
+    }
+    resolved(get_longitude_timezone(coordinates.longitude), TimezoneSource::Longitude)
+}
+
+// search in growing circles around the location, so the closest timezone is found first
+fn get_nearest_timezone(coordinates: &Coordinates, lookup: impl Fn(&Coordinates) -> Option<Tz>) -> Option<Tz> {
+    const SEARCH_RADII: [f64; 6] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.0]; // in degrees
+    const SEARCH_DIRECTIONS: u32 = 16;
+
+    SEARCH_RADII.iter()
+        .flat_map(|radius| (0..SEARCH_DIRECTIONS).map(move |direction| {
+            let angle = f64::from(direction) * std::f64::consts::TAU / f64::from(SEARCH_DIRECTIONS);
+            Coordinates::new(
+                (coordinates.latitude + radius * angle.sin()).clamp(-90.0, 90.0),
+                // wrap around the antimeridian
+                (coordinates.longitude + radius * angle.cos() + 540.0) % 360.0 - 180.0,
+            )
+        }))
+        .find_map(|candidate| lookup(&candidate))
+}
+
+/// Returns the timezone most of the population of the country lives in, if the country is one of the
+/// frequently searched ones.
+pub fn get_country_timezone(country_code: &str) -> Option<Tz> {
+    let code = country_code.to_lowercase();
+    COUNTRY_TIMEZONES.iter()
+        .find(|(country, _)| *country == code)
+        .map(|(_, timezone)| *timezone)
+}
+
+// the IANA timezones named Etc/GMT-X have an offset of +X hours, since their sign follows POSIX
+fn get_longitude_timezone(longitude: f64) -> Tz {
+    let hours = (longitude / 15.0).round() as i32;
+    let name = match hours {
+        0 => "Etc/GMT".to_string(),
+        hours => format!("Etc/GMT{:+}", -hours),
+    };
+    name.parse().unwrap_or(Tz::UTC)
+}
+
+/// Returns the offset of the `timezone` to UTC at this moment.
+pub fn current_offset(timezone: &Tz) -> FixedOffset {
+    Utc::now().with_timezone(timezone).offset().fix()
+}
+
+/// Returns the first instant of the given `date` in the `timezone`, which is usually midnight, but
+/// may be later if the clocks are turned forward at midnight.
+pub fn start_of_day(timezone: &Tz, date: NaiveDate) -> DateTime<Utc> {
+    // transitions happen in steps of at most an hour, so try every full hour until one exists
+    (0..24)
+        .filter_map(|hour| NaiveTime::from_hms_opt(hour, 0, 0))
+        .find_map(|time| timezone.from_local_datetime(&date.and_time(time)).earliest())
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| date.and_time(NaiveTime::MIN).and_utc())
+}
+
+/// A local date and time mapped to an actual instant in a timezone.
+#[derive(Debug, Clone, Copy)]
+pub enum LocalizedTime {
+    Exact(DateTime<Tz>),
+    /// the `requested` local time was skipped when the clocks were turned forward, so it was moved past the gap
+    Shifted { requested: NaiveDateTime, shifted: DateTime<Tz> },
+    /// the local time occurred twice since the clocks were turned back, the earlier one is taken
+    Ambiguous { earlier: DateTime<Tz>, later: DateTime<Tz> },
+}
+impl LocalizedTime {
+    pub fn instant(&self) -> &DateTime<Tz> {
+        match self {
+            LocalizedTime::Exact(instant) | LocalizedTime::Shifted { shifted: instant, .. } => instant,
+            LocalizedTime::Ambiguous { earlier, .. } => earlier,
+        }
+    }
+}
+
+/// Maps the `local` date and time to an instant in the `timezone`, even if it does not exist or
+/// exists twice due to a DST transition.
+pub fn localize(timezone: &Tz, local: &NaiveDateTime) -> LocalizedTime {
+    match timezone.from_local_datetime(local) {
+        LocalResult::Single(instant) => LocalizedTime::Exact(instant),
+        LocalResult::Ambiguous(earlier, later) => LocalizedTime::Ambiguous { earlier, later },
+        LocalResult::None => {
+            // applying the offset from before the gap moves the time forward by the length of the gap,
+            // e.g. 02:30 becomes 03:30 if the clocks jump from 02:00 to 03:00
+            let offset_before = timezone.offset_from_utc_datetime(&(*local - TimeDelta::days(1))).fix();
+            let utc = *local - TimeDelta::seconds(offset_before.local_minus_utc() as i64);
+            LocalizedTime::Shifted { requested: *local, shifted: timezone.from_utc_datetime(&utc) }
+        }
+    }
+}
+
+/// A change of the offset of a timezone to UTC, e.g. the start or end of daylight saving time.
+#[derive(Debug, Clone)]
+pub struct Transition {
+    /// the first instant with the new offset
+    pub instant: DateTime<Utc>,
+    pub offset_before: FixedOffset,
+    pub offset_after: FixedOffset,
+    pub abbreviation_before: String,
+    pub abbreviation_after: String,
+}
+impl Transition {
+    /// Returns whether the clocks are turned forward, i.e. whether a local hour is skipped.
+    pub fn is_forward(&self) -> bool {
+        self.offset_after.local_minus_utc() > self.offset_before.local_minus_utc()
+    }
+}
+
+// transitions happen at most a few times per year, so a year and a bit covers the yearly DST cycle
+const TRANSITION_SEARCH_DAYS: i64 = 400;
+
+/// Returns the last transition of the `timezone` at or before `from`, if there was one within about a year.
+pub fn previous_transition(timezone: &Tz, from: &DateTime<Utc>) -> Option<Transition> {
+    find_transition(timezone, from, -1)
+}
+
+/// Returns the first transition of the `timezone` after `from`, if there is one within about a year.
+pub fn next_transition(timezone: &Tz, from: &DateTime<Utc>) -> Option<Transition> {
+    find_transition(timezone, from, 1)
+}
+
+// walk in steps of a day until the offset changes, then narrow the transition down to the second
+fn find_transition(timezone: &Tz, from: &DateTime<Utc>, direction: i64) -> Option<Transition> {
+    let offset_at = |instant: &DateTime<Utc>| {
+        let offset = timezone.offset_from_utc_datetime(&instant.naive_utc());
+        (offset.fix(), offset.to_string())
+    };
+
+    let from = DateTime::from_timestamp(from.timestamp(), 0).unwrap_or(*from);
+    let start = offset_at(&from);
+    let (mut before, mut after) = (1..=TRANSITION_SEARCH_DAYS)
+        .map(|days| from + TimeDelta::days(days * direction))
+        .find(|instant| offset_at(instant) != start)
+        .map(|changed| match direction {
+            d if d > 0 => (changed - TimeDelta::days(1), changed),
+            _ => (changed, changed + TimeDelta::days(1)),
+        })?;
+
+    while after - before > TimeDelta::seconds(1) {
+        let middle = before + TimeDelta::seconds((after - before).num_seconds() / 2);
+        if offset_at(&middle) == offset_at(&before) {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+
+    let ((offset_before, abbreviation_before), (offset_after, abbreviation_after)) = (offset_at(&before), offset_at(&after));
+    Some(Transition { instant: after, offset_before, offset_after, abbreviation_before, abbreviation_after })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // pretends that there is land with the timezone of Vienna north of 10°N only
+    fn northern_land(coordinates: &Coordinates) -> Option<Tz> {
+        (coordinates.latitude > 10.0).then_some(Tz::Europe__Vienna)
+    }
+
+    #[test]
+    fn exact_timezone() {
+        let resolved = resolve_timezone_with(&Coordinates::new(47.07, 15.44), Some("at"), northern_land);
+        assert_eq!(resolved.timezone, Tz::Europe__Vienna);
+        assert_eq!(resolved.source, TimezoneSource::Exact);
+    }
+
+    #[test]
+    fn nearest_land_before_country() {
+        let resolved = resolve_timezone_with(&Coordinates::new(9.5, 15.44), Some("jp"), northern_land);
+        assert_eq!(resolved.timezone, Tz::Europe__Vienna);
+        assert_eq!(resolved.source, TimezoneSource::NearestLand);
+    }
+
+    #[test]
+    fn country_without_land_nearby() {
+        let resolved = resolve_timezone_with(&Coordinates::new(-20.0, 15.44), Some("JP"), northern_land);
+        assert_eq!(resolved.timezone, Tz::Asia__Tokyo);
+        assert_eq!(resolved.source, TimezoneSource::Country);
+    }
+
+    #[test]
+    fn longitude_as_last_resort() {
+        for country_code in [None, Some("gl")] {
+            let resolved = resolve_timezone_with(&Coordinates::new(-20.0, -140.0), country_code, northern_land);
+            assert_eq!(resolved.timezone, Tz::Etc__GMTPlus9);
+            assert_eq!(resolved.source, TimezoneSource::Longitude);
+        }
+
+        assert_eq!(get_longitude_timezone(15.44), Tz::Etc__GMTMinus1);
+        assert_eq!(get_longitude_timezone(-7.0), Tz::Etc__GMT);
+        assert_eq!(get_longitude_timezone(180.0), Tz::Etc__GMTMinus12);
+    }
+
+    fn local(date_time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn exact_local_time() {
+        let LocalizedTime::Exact(instant) = localize(&Tz::Europe__Vienna, &local("2024-07-01 12:00")) else {
+            panic!("unique local time not exact");
+        };
+        assert_eq!(instant.naive_utc(), local("2024-07-01 10:00"));
+    }
+
+    #[test]
+    fn skipped_local_time_is_shifted() {
+        let localized = localize(&Tz::Europe__Vienna, &local("2024-03-31 02:30"));
+        let LocalizedTime::Shifted { requested, shifted } = localized else {
+            panic!("skipped local time not shifted");
+        };
+        assert_eq!(requested, local("2024-03-31 02:30"));
+        assert_eq!(shifted.naive_local(), local("2024-03-31 03:30"));
+        assert_eq!(shifted.naive_utc(), local("2024-03-31 01:30"));
+        assert_eq!(localized.instant(), &shifted);
+    }
+
+    #[test]
+    fn repeated_local_time_takes_the_earlier() {
+        let localized = localize(&Tz::Europe__Vienna, &local("2024-10-27 02:30"));
+        let LocalizedTime::Ambiguous { earlier, later } = localized else {
+            panic!("repeated local time not ambiguous");
+        };
+        assert_eq!(earlier.naive_utc(), local("2024-10-27 00:30"));
+        assert_eq!(later.naive_utc(), local("2024-10-27 01:30"));
+        assert_eq!(localized.instant(), &earlier);
+    }
+
+    #[test]
+    fn days_with_transitions_are_shorter_or_longer() {
+        let day_length = |timezone: &Tz, day: &str| {
+            let day = date(day);
+            start_of_day(timezone, day.succ_opt().unwrap()) - start_of_day(timezone, day)
+        };
+        assert_eq!(day_length(&Tz::Europe__Vienna, "2024-03-31"), TimeDelta::hours(23));
+        assert_eq!(day_length(&Tz::Europe__Vienna, "2024-10-27"), TimeDelta::hours(25));
+        assert_eq!(day_length(&Tz::Europe__Vienna, "2024-07-01"), TimeDelta::hours(24));
+    }
+
+    #[test]
+    fn day_starts_after_a_gap_at_midnight() {
+        // Chile turns the clocks from 00:00 to 01:00 at the start of daylight saving time
+        assert_eq!(start_of_day(&Tz::America__Santiago, date("2024-09-08")).naive_utc(), local("2024-09-08 04:00"));
+        assert_eq!(start_of_day(&Tz::Europe__Vienna, date("2024-03-31")).naive_utc(), local("2024-03-30 23:00"));
+    }
+
+    fn utc(date_time: &str) -> DateTime<Utc> {
+        local(date_time).and_utc()
+    }
+
+    #[test]
+    fn next_transition_to_summer_time() {
+        let transition = next_transition(&Tz::Europe__Vienna, &utc("2024-01-01 00:00")).unwrap();
+        assert_eq!(transition.instant, utc("2024-03-31 01:00"));
+        assert!(transition.is_forward());
+        assert_eq!(transition.offset_before.local_minus_utc(), 3600);
+        assert_eq!(transition.offset_after.local_minus_utc(), 7200);
+        assert_eq!((transition.abbreviation_before.as_str(), transition.abbreviation_after.as_str()), ("CET", "CEST"));
+    }
+
+    #[test]
+    fn previous_transition_to_winter_time() {
+        let transition = previous_transition(&Tz::Europe__Vienna, &utc("2024-12-24 18:00")).unwrap();
+        assert_eq!(transition.instant, utc("2024-10-27 01:00"));
+        assert!(!transition.is_forward());
+        assert_eq!((transition.abbreviation_before.as_str(), transition.abbreviation_after.as_str()), ("CEST", "CET"));
+    }
+
+    #[test]
+    fn transition_at_the_boundary() {
+        let at_transition = utc("2024-03-31 01:00");
+        assert_eq!(previous_transition(&Tz::Europe__Vienna, &at_transition).unwrap().instant, at_transition);
+        assert_eq!(next_transition(&Tz::Europe__Vienna, &at_transition).unwrap().instant, utc("2024-10-27 01:00"));
+
+        // fractions of a second do not keep the search from finding the exact second
+        let just_before = at_transition - TimeDelta::milliseconds(1500);
+        assert_eq!(next_transition(&Tz::Europe__Vienna, &just_before).unwrap().instant, at_transition);
+    }
+
+    #[test]
+    fn no_transitions_without_daylight_saving_time() {
+        for timezone in [Tz::UTC, Tz::Asia__Tokyo] {
+            assert!(next_transition(&timezone, &utc("2024-01-01 00:00")).is_none());
+            assert!(previous_transition(&timezone, &utc("2024-01-01 00:00")).is_none());
+        }
+    }
+}
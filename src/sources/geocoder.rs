@@ -0,0 +1,231 @@
+use super::common::{ApiError, Coordinates};
+use super::nominatim::{FeatureKind, Place, SearchRegion};
+use super::place_query::PlaceQuery;
+use super::rate_limit::Requester;
+use fluent_templates::LanguageIdentifier;
+use super::{nominatim, open_meteo_geocoding, photon, place_deduplication};
+use log::{info, warn};
+use std::fmt;
+
+const GEOCODERS_ENV: &str = "GEOCODERS";
+#[cfg(not(feature = "offline-geocoding"))]
+const DEFAULT_GEOCODERS: &str = "nominatim, photon, open-meteo";
+#[cfg(feature = "offline-geocoding")]
+const DEFAULT_GEOCODERS: &str = "offline, nominatim, photon, open-meteo";
+
+/// A service that can find places by their name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backend {
+    /// a Nominatim instance at the given host, either the public one or a self-hosted one
+    Nominatim(String),
+    /// a Photon instance at the given host
+    Photon(String),
+    /// the geocoding API of Open-Meteo, which is based on GeoNames
+    OpenMeteo,
+    /// the GeoNames extract bundled with the binary
+    #[cfg(feature = "offline-geocoding")]
+    Offline,
+}
+impl Backend {
+    /// Parses a backend in the form `<kind>` or `<kind>:<host>`, e.g. `nominatim:https://nominatim.example.org`.
+    pub fn parse(definition: &str) -> Option<Backend> {
+        let (kind, host) = match definition.trim().split_once(':') {
+            Some((kind, host)) => (kind, Some(host.trim().to_string())),
+            None => (definition.trim(), None),
+        };
+
+        match kind.to_lowercase().as_str() {
+            "nominatim" => Some(Backend::Nominatim(host.unwrap_or_else(|| nominatim::PUBLIC_HOST.to_string()))),
+            "photon" => Some(Backend::Photon(host.unwrap_or_else(|| photon::PUBLIC_HOST.to_string()))),
+            "open-meteo" => Some(Backend::OpenMeteo),
+            #[cfg(feature = "offline-geocoding")]
+            "offline" => Some(Backend::Offline),
+            _ => None,
+        }
+    }
+
+    /// Whether the backend may be asked for suggestions while the user is typing. The usage policy of
+    /// the public Nominatim instance forbids autocompletion, so only self-hosted ones are asked.
+    pub fn allows_autocomplete(&self) -> bool {
+        match self {
+            Backend::Nominatim(host) => host.trim_end_matches('/') != nominatim::PUBLIC_HOST,
+            Backend::Photon(_) => true,
+            Backend::OpenMeteo => false,
+            #[cfg(feature = "offline-geocoding")]
+            Backend::Offline => true,
+        }
+    }
+
+    async fn query_place(&self, client: &reqwest::Client, requester: Requester, query: &PlaceQuery, kind: FeatureKind,
+                         region: &SearchRegion, language: &str) -> Result<Vec<Place>, ApiError>
+    {
+        match self {
+            Backend::Nominatim(host) => nominatim::query_place(client, requester, host, query, kind, region, language).await,
+            Backend::Photon(host) => photon::query_place(client, host, query, kind, region, language).await,
+            Backend::OpenMeteo => open_meteo_geocoding::query_place(client, query, kind, region, language).await,
+            #[cfg(feature = "offline-geocoding")]
+            Backend::Offline => Ok(super::offline_geocoding::query_place(query, kind, region)),
+        }
+    }
+}
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::Nominatim(host) => write!(f, "Nominatim ({})", host),
+            Backend::Photon(host) => write!(f, "Photon ({})", host),
+            Backend::OpenMeteo => write!(f, "Open-Meteo"),
+            #[cfg(feature = "offline-geocoding")]
+            Backend::Offline => write!(f, "GeoNames (offline)"),
+        }
+    }
+}
+
+/// Finds places using a list of geocoding backends, falling back to the next one whenever a backend
+/// fails or cannot find anything.
+#[derive(Debug, Clone)]
+pub struct Geocoder {
+    backends: Vec<Backend>,
+}
+impl Geocoder {
+    pub fn new(backends: Vec<Backend>) -> Geocoder {
+        Geocoder { backends }
+    }
+
+    /// Reads the comma-separated list of backends from the ENV variable `GEOCODERS`.
+    pub fn from_env() -> Geocoder {
+        let definitions = std::env::var(GEOCODERS_ENV).unwrap_or_else(|_| DEFAULT_GEOCODERS.to_string());
+
+        let backends: Vec<Backend> = definitions.split(',')
+            .filter(|definition| !definition.trim().is_empty())
+            .filter_map(|definition| {
+                let backend = Backend::parse(definition);
+                if backend.is_none() {
+                    warn!("Ignoring unknown geocoder '{}'", definition.trim());
+                }
+                backend
+            })
+            .collect();
+
+        if backends.is_empty() {
+            warn!("No valid geocoders configured in '{}'. Falling back to '{}'", GEOCODERS_ENV, DEFAULT_GEOCODERS);
+            return Geocoder::new(DEFAULT_GEOCODERS.split(',').filter_map(Backend::parse).collect());
+        }
+
+        Geocoder::new(backends)
+    }
+
+    /// Searches for places matching the `query`. A country given in the query replaces the `region`.
+    /// Addresses are requested in the given `language` where the backend supports it.
+    pub async fn query_place(&self, client: &reqwest::Client, requester: Requester, query: &PlaceQuery,
+                             kind: FeatureKind, region: &SearchRegion, language: &LanguageIdentifier)
+        -> Result<Vec<Place>, ApiError>
+    {
+        Geocoder::search(self.backends.iter(), client, requester, query, kind, region, language).await
+    }
+
+    /// Like [Geocoder::query_place], but only asks the backends that allow suggestions while the user
+    /// is typing, see [Backend::allows_autocomplete].
+    pub async fn autocomplete_place(&self, client: &reqwest::Client, requester: Requester, query: &PlaceQuery,
+                                    kind: FeatureKind, region: &SearchRegion, language: &LanguageIdentifier)
+        -> Result<Vec<Place>, ApiError>
+    {
+        let backends = self.backends.iter().filter(|backend| backend.allows_autocomplete());
+        Geocoder::search(backends, client, requester, query, kind, region, language).await
+    }
+
+    async fn search<'a>(backends: impl Iterator<Item = &'a Backend>, client: &reqwest::Client, requester: Requester,
+                        query: &PlaceQuery, kind: FeatureKind, region: &SearchRegion, language: &LanguageIdentifier)
+        -> Result<Vec<Place>, ApiError>
+    {
+        let region = query.region(region);
+        let language = language.language.as_str();
+        let mut last_error = None;
+
+        for backend in backends {
+            match backend.query_place(client, requester, query, kind, &region, language).await {
+                Ok(places) if !places.is_empty() => return Ok(place_deduplication::deduplicate(places)),
+                Ok(_) => info!("{} found no place matching '{}', trying next geocoder", backend, query),
+                Err(e) => {
+                    warn!("{} failed to search for '{}': {}", backend, query, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        // only report that nothing was found if no backend failed, since the failing one might have found it
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Looks up a place by its reference (see [Place::reference]). OSM references are looked up in
+    /// every Nominatim backend.
+    pub async fn lookup_place(&self, client: &reqwest::Client, requester: Requester, reference: &str,
+                              language: &LanguageIdentifier) -> Result<Vec<Place>, ApiError>
+    {
+        #[cfg(feature = "offline-geocoding")]
+        if let Some(place) = super::offline_geocoding::lookup_place(reference) {
+            return Ok(vec![place]);
+        }
+
+        let language = language.language.as_str();
+        let mut last_error = None;
+
+        for host in self.nominatim_hosts() {
+            match nominatim::lookup_place(client, requester, host, reference, language).await {
+                Ok(places) if !places.is_empty() => return Ok(places),
+                Ok(_) => (),
+                Err(e) => {
+                    warn!("Nominatim ({}) failed to look up '{}': {}", host, reference, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Returns a [Place] located exactly at the given `coordinates`, which is described by the address
+    /// of the surrounding area according to a reverse lookup in the first available Nominatim backend.
+    /// Coordinates without any surrounding address, e.g. in the middle of the ocean, yield a place
+    /// without address.
+    pub async fn locate_coordinates(&self, client: &reqwest::Client, requester: Requester, coordinates: &Coordinates,
+                                    language: &LanguageIdentifier) -> Result<Place, ApiError>
+    {
+        let language = language.language.as_str();
+        let mut surrounding = None;
+
+        for host in self.nominatim_hosts() {
+            match nominatim::reverse_place(client, requester, host, coordinates, language).await {
+                Ok(place) => {
+                    surrounding = Some(place);
+                    break;
+                },
+                Err(ApiError::NotFound) => break,
+                Err(e) => warn!("Nominatim ({}) failed to look up {}: {}", host, coordinates, e),
+            }
+        }
+
+        // the exact position is known in any case, so a failed lookup only costs the address
+        Ok(Place::at_coordinates(coordinates, surrounding))
+    }
+
+    // reverse and OSM reference lookups are only supported by Nominatim, so always include the public instance
+    fn nominatim_hosts(&self) -> Vec<&str> {
+        let mut hosts: Vec<&str> = self.backends.iter()
+            .filter_map(|backend| match backend {
+                Backend::Nominatim(host) => Some(host.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        if !hosts.contains(&nominatim::PUBLIC_HOST) {
+            hosts.push(nominatim::PUBLIC_HOST);
+        }
+        hosts
+    }
+}
@@ -2,4 +2,13 @@ pub mod nominatim;
 pub mod climate_historical;
 pub mod climate_forecast;
 pub mod common;
-pub mod geo_time;
\ No newline at end of file
+pub mod geo_time;
+pub mod rate_limit;
+pub mod geocoder;
+pub mod photon;
+pub mod open_meteo_geocoding;
+pub mod place_ranking;
+pub mod place_query;
+pub mod place_deduplication;
+#[cfg(feature = "offline-geocoding")]
+pub mod offline_geocoding;
\ No newline at end of file
@@ -1,31 +1,51 @@
 use super::common::*;
+use super::place_query::{self, Country, PlaceQuery};
+use super::rate_limit::{RateLimiter, Requester};
 use crate::sources::common;
 use bot_macros::collect_fields;
 use cached::proc_macro::cached;
-use cached::SizedCache;
+use cached::{Cached, SizedCache};
+use crate::utils::parsing;
 use fluent_templates::{LanguageIdentifier};
-use serde::Deserialize;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 use AddressLevel::*;
 
 
-const BASE_URL: &str = "https://nominatim.openstreetmap.org/search?format=jsonv2&limit=10&\
-                        addressdetails=1&namedetails=1&extratags=1&\
-                        featureType=settlement&\
-                        viewbox=55.030541,5.324132,45.850230,17.435780";
+pub const PUBLIC_HOST: &str = "https://nominatim.openstreetmap.org";
+const SEARCH_PATH: &str = "/search?format=jsonv2&limit=10&addressdetails=1&namedetails=1&extratags=1";
+const LOOKUP_PATH: &str = "/lookup?format=jsonv2&addressdetails=1&namedetails=1&extratags=1";
+const REVERSE_PATH: &str = "/reverse?format=jsonv2&zoom=14&addressdetails=1&namedetails=1&extratags=1";
 const CACHED_ITEMS: usize = 200;
+/// Category of places originating from GeoNames instead of OSM, whose references look like `G2778067`.
+pub const GEONAMES_CATEGORY: &str = "geonames";
+pub const GEONAMES_REFERENCE_PREFIX: char = 'G';
+// the usage policy of the public Nominatim instance allows at most one request per second
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_QUEUED_REQUESTS: usize = 20;
+
+lazy_static! {
+    static ref RATE_LIMITER: RateLimiter = RateLimiter::new(REQUEST_INTERVAL, MAX_QUEUED_REQUESTS);
+}
 
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 #[allow(dead_code)]
 pub struct Place {
     #[serde(rename = "place_id")]
     pub id: i64,
+    pub osm_type: Option<String>,
+    pub osm_id: Option<i64>,
     pub lat: String,
     pub lon: String,
     pub category: String,
-    #[serde(rename = "namedetails")]
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(rename = "namedetails", default, deserialize_with = "common::null_as_default")]
     pub name: PlaceName,
     #[serde(rename = "addresstype")]
     pub address_type: AddressLevel,
@@ -37,17 +57,84 @@ pub struct Place {
     pub importance: f32,
 }
 impl Place {
-    pub fn address_details(&self) -> String {
-        AddressLevel::HIERARCHY.iter()
+    /// Creates a place from the data of a source other than Nominatim. Fields that are not covered by
+    /// the parameters are left empty and may be filled in afterwards.
+    pub fn new(id: i64, coordinates: &Coordinates, name: &str, address_type: AddressLevel, address: Address) -> Place {
+        let mut place = Place {
+            id,
+            osm_type: None,
+            osm_id: None,
+            lat: coordinates.latitude.to_string(),
+            lon: coordinates.longitude.to_string(),
+            category: String::new(),
+            kind: String::new(),
+            name: PlaceName { local: name.to_string(), global: HashMap::new() },
+            place_rank: address_type.typical_rank(),
+            address_type,
+            address,
+            full_name: String::new(),
+            extratags: None,
+            importance: 0.0,
+        };
+        place.full_name = place.address_details(&crate::QUERY_LANG);
+
+        place
+    }
+
+    /// Creates a place located exactly at the given `coordinates`. If known, the `surrounding` place
+    /// is used to describe the address of the location.
+    pub fn at_coordinates(coordinates: &Coordinates, surrounding: Option<Place>) -> Place {
+        let mut place = surrounding.unwrap_or_else(|| {
+            Place::new(0, coordinates, "", Other(String::new()), Address::default())
+        });
+
+        place.lat = coordinates.latitude.to_string();
+        place.lon = coordinates.longitude.to_string();
+        place.name = PlaceName { local: coordinates.to_string(), global: HashMap::new() };
+        place.address_type = Other("coordinates".to_string());
+        // tags like the elevation belong to the surrounding place and not to this exact location
+        place.extratags = None;
+        place.osm_type = None;
+        place.osm_id = None;
+
+        place
+    }
+
+    /// Returns a short reference that uniquely identifies this place, i.e. its [OSM reference](Place::osm_reference)
+    /// or its GeoNames ID prefixed with `G`.
+    pub fn reference(&self) -> Option<String> {
+        if self.category == GEONAMES_CATEGORY {
+            return Some(format!("{}{}", GEONAMES_REFERENCE_PREFIX, self.id));
+        }
+        self.osm_reference()
+    }
+
+    /// Returns the short form of the OSM object this place refers to, e.g. `R78112` for a relation,
+    /// which uniquely identifies the place across Nominatim instances.
+    pub fn osm_reference(&self) -> Option<String> {
+        let type_letter = self.osm_type.as_ref()?.chars().next()?.to_ascii_uppercase();
+        Some(format!("{}{}", type_letter, self.osm_id?))
+    }
+
+    /// Lists all levels of the address from the most to the least specific one, naming the place itself
+    /// in the given language if a translation is known.
+    pub fn address_details(&self, lang: &LanguageIdentifier) -> String {
+        let mut levels = AddressLevel::HIERARCHY.iter()
             .map(|level| self.address.get_address_level(level))
             .filter_map(|opt| opt.to_owned()) // filter out Nones and dereference Somes
             .collect::<Vec<_>>()
-            .concat()
-            .join(", ")
+            .concat();
+
+        // natural features like peaks are not part of the address hierarchy, so prepend their name
+        if let Other(_) = self.address_type {
+            levels.insert(0, self.name.local.clone());
+        }
+
+        self.localize_own_name(levels, lang).join(", ")
     }
 
-    pub fn address_summary(&self) -> String {
-        self.address_type.related_address_levels().iter()
+    pub fn address_summary(&self, lang: &LanguageIdentifier) -> String {
+        let mut summary = self.address_type.related_address_levels().iter()
             .map(|level| self.address.get_address_level(level))
             .filter_map(|option| { // filter out Nones but take the first string of Somes
                 if let Some(list) = option {
@@ -58,8 +145,83 @@ impl Place {
 
                 None
             })
-            .collect::<Vec<String>>()
-            .join(", ")
+            .collect::<Vec<String>>();
+
+        if let Other(_) = self.address_type {
+            summary.insert(0, self.name.local.clone());
+        }
+
+        self.localize_own_name(summary, lang).join(", ")
+    }
+
+    /// Returns the name of the place in the given language, or its local name if no translation is known.
+    pub fn localized_name(&self, lang: &LanguageIdentifier) -> &str {
+        self.name.get_lang_or_default(lang).unwrap_or(&self.name.local)
+    }
+
+    // the address levels come from the source as they are, so replace the local name of the place itself
+    fn localize_own_name(&self, levels: Vec<String>, lang: &LanguageIdentifier) -> Vec<String> {
+        let localized = self.localized_name(lang);
+
+        levels.into_iter()
+            .map(|level| if level == self.name.local { localized.to_string() } else { level })
+            .collect()
+    }
+
+    /// A short, single-line description of the place including the country flag. If the local name
+    /// differs from the one in the given language, the latter is added in parentheses.
+    pub fn label(&self, lang: &LanguageIdentifier) -> String {
+        let country_letters = self.country_indicator();
+        let mut summary = self.address_summary(lang);
+
+        let localized = self.localized_name(lang);
+        if !self.name.local.is_empty() && !self.name.local.contains(localized) {
+            let replacement = format!("{} ({})", self.name, localized);
+            summary = summary.replacen(localized, &replacement, 1);
+        }
+
+        format!("{} | {}", country_letters, summary)
+    }
+
+    /// Returns the elevation of the place in metres above sea level, if it is tagged with one.
+    /// OSM `ele` values are supposed to be plain metres, but may contain a unit suffix
+    /// (`2369 m`) or several values separated by semicolons, so only the first number is taken.
+    pub fn elevation(&self) -> Option<f32> {
+        let ele = self.extratags.as_ref()?.ele.as_deref()?;
+
+        ele.split(';').next()?
+            .trim()
+            .trim_end_matches('m')
+            .trim()
+            .replace(',', ".")
+            .parse::<f32>().ok()
+    }
+
+    /// Returns the number of inhabitants, if the place is tagged with it.
+    pub fn population(&self) -> Option<u64> {
+        let population = self.extratags.as_ref()?.population.as_deref()?;
+        // thousands separators are not allowed in OSM, but nevertheless used from time to time
+        population.replace([',', '.', ' '], "").parse().ok()
+    }
+
+    /// Returns the link to this place on openstreetmap.org, if it originates from OSM.
+    pub fn osm_url(&self) -> Option<String> {
+        let osm_type = match self.osm_type.as_deref()?.to_lowercase().as_str() {
+            "n" | "node" => "node",
+            "w" | "way" => "way",
+            "r" | "relation" => "relation",
+            _ => return None,
+        };
+        Some(format!("https://www.openstreetmap.org/{}/{}", osm_type, self.osm_id?))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.name.local).chain(self.name.global.values())
+    }
+
+    pub fn is_natural_feature(&self) -> bool {
+        FeatureKind::NATURAL_TAGS.iter()
+            .any(|(category, kind)| self.category == *category && self.kind == *kind)
     }
 
     pub fn country_indicator(&self) -> String {
@@ -78,22 +240,6 @@ impl Place {
         }
     }
 }
-impl fmt::Display for Place {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let country_letters = self.country_indicator();
-        let mut summary = self.address_summary();
-
-        // if the local place name differs from its name in the app language, add the latter in parentheses
-        if let Some(expected) = self.name.get_lang(&crate::QUERY_LANG) {
-            if !self.name.local.contains(expected) {
-                let replacement = format!("{} ({})", self.name, expected);
-                summary = summary.replacen(&self.name.to_string(), &replacement, 1);
-            }
-        }
-
-        write!(f, "{} | {}", country_letters, summary)
-    }
-}
 impl fmt::Debug for Place {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} [lat: {}, lon: {}]", &self.name, &self.lat, &self.lon)
@@ -108,7 +254,7 @@ impl From<&Place> for Option<Coordinates> {
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[collect_fields({
    neighbourhood = [neighbourhood, allotments, quarter],
    district = [suburb, city_district, subdivision, borough],
@@ -127,7 +273,6 @@ pub struct Address {
     town: Option<String>,
     city: Option<String>,
 
-    district: Option<String>,
     city_district: Option<String>,
     borough: Option<String>,
     suburb: Option<String>,
@@ -156,6 +301,22 @@ pub struct Address {
     pub iso3166_l6: Option<String>,
 }
 impl Address {
+    /// Sets the name of the given address `level`, used to build addresses from sources other than Nominatim.
+    pub fn with_level(mut self, level: &AddressLevel, name: Option<String>) -> Self {
+        match level {
+            Neighbourhood => self.neighbourhood = name,
+            District => self.city_district = name,
+            Hamlet => self.hamlet = name,
+            Municipality => self.municipality = name,
+            County => self.county = name,
+            State => self.state = name,
+            Country => self.country = name,
+            Continent => self.continent = name,
+            Other(_) => (),
+        }
+        self
+    }
+
     #[inline]
     pub fn get_address_level(&self, level: &AddressLevel) -> Option<Vec<String>> {
         match level {
@@ -172,7 +333,7 @@ impl Address {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum AddressLevel {
     #[serde(rename = "neighbourhood", alias = "quarter", alias = "allotments")]
     Neighbourhood,
@@ -196,6 +357,27 @@ pub enum AddressLevel {
 impl AddressLevel {
     const HIERARCHY: [AddressLevel; 6] = [Neighbourhood, District, Hamlet, Municipality, County, State];
 
+    /// Maps an OSM tag value like `town` or `suburb` to its address level.
+    pub fn from_tag(tag: &str) -> AddressLevel {
+        serde_json::from_value(serde_json::Value::String(tag.to_string()))
+            .unwrap_or_else(|_| Other(tag.to_string()))
+    }
+
+    /// The `place_rank` Nominatim usually assigns to places of this address level.
+    pub fn typical_rank(&self) -> i16 {
+        match self {
+            Continent => 2,
+            Country => 4,
+            State => 8,
+            County => 12,
+            Municipality => 16,
+            District => 20,
+            Hamlet => 20,
+            Neighbourhood => 22,
+            Other(_) => 30,
+        }
+    }
+
     pub fn related_address_levels(&self) -> &'static [AddressLevel] {
         match self {
             Neighbourhood => &[Neighbourhood, District, Municipality, State],
@@ -211,9 +393,9 @@ impl AddressLevel {
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct PlaceName {
-    #[serde(rename = "name")]
+    #[serde(rename = "name", default)]
     pub local: String,
 
     #[serde(flatten)]
@@ -232,6 +414,19 @@ impl PlaceName {
         self.get_lang(lang).or(self.get_lang(default_lang))
     }
 
+    /// Returns all translations of the name as pairs of language code and name, ordered by language.
+    pub fn translations(&self) -> Vec<(&str, &String)> {
+        let mut translations: Vec<(&str, &String)> = self.global.iter()
+            .filter_map(|(key, name)| Some((key.strip_prefix(Self::NAME_PREFIX)?, name)))
+            .collect();
+        translations.sort();
+        translations
+    }
+
+    pub fn add_translation(&mut self, language: &str, name: String) {
+        self.global.insert(format!("{}{}", Self::NAME_PREFIX, language), name);
+    }
+
     pub fn get_lang_or_default(&self, lang: &LanguageIdentifier) -> Option<&String> {
         self.get_lang_or(lang, &crate::FALLBACK_LANGUAGE)
     }
@@ -242,7 +437,7 @@ impl fmt::Display for PlaceName {
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Extratags {
     pub wikidata: Option<String>,
     pub wikipedia: Option<String>,
@@ -250,6 +445,172 @@ pub struct Extratags {
     pub capital: Option<String>,
     pub population: Option<String>,
     pub population_date: Option<String>,
+    pub ele: Option<String>,
+}
+impl Extratags {
+    /// Wikipedia articles are tagged as `<language>:<title>`, e.g. `de:Graz`.
+    pub fn wikipedia_url(&self) -> Option<String> {
+        let (language, title) = self.wikipedia.as_deref()?.split_once(':')?;
+        // titles may contain characters like `?` or `#`, while spaces are written as underscores
+        let title: String = url::form_urlencoded::byte_serialize(title.trim().replace(' ', "_").as_bytes()).collect();
+        Some(format!("https://{}.wikipedia.org/wiki/{}", language.trim(), title))
+    }
+
+    pub fn wikidata_url(&self) -> Option<String> {
+        self.wikidata.as_ref().map(|id| format!("https://www.wikidata.org/wiki/{}", id.trim()))
+    }
+}
+
+/// The kind of geographic feature a place search is looking for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, poise::ChoiceParameter)]
+pub enum FeatureKind {
+    #[default]
+    #[name = "settlement"]
+    Settlement,
+    #[name = "summit"]
+    #[name = "peak, pass or hut"]
+    Natural,
+}
+impl FeatureKind {
+    // (category, type) pairs of OSM tags accepted as natural features
+    pub const NATURAL_TAGS: [(&'static str, &'static str); 8] = [
+        ("natural", "peak"),
+        ("natural", "volcano"),
+        ("natural", "saddle"),
+        ("natural", "ridge"),
+        ("natural", "hill"),
+        ("mountain_pass", "yes"),
+        ("tourism", "alpine_hut"),
+        ("tourism", "wilderness_hut"),
+    ];
+
+    fn search_params(&self, query: &PlaceQuery) -> Vec<(&'static str, String)> {
+        match self {
+            // the structured 'city' search only ever matches settlements
+            FeatureKind::Settlement => {
+                let mut params = vec![
+                    ("city", query.name.clone()),
+                    ("featureType", "settlement".to_string()),
+                ];
+                params.extend(query.postcode.clone().map(|postcode| ("postalcode", postcode)));
+                params.extend(query.state.clone().map(|state| ("state", state)));
+                // country codes are passed on as search region instead
+                if let Some(Country::Name(country)) = &query.country {
+                    params.push(("country", country.clone()));
+                }
+                params
+            },
+            // structured and free-form searches cannot be combined, and the layer filter requires the latter
+            FeatureKind::Natural => vec![
+                ("q", query.free_form()),
+                ("layer", "natural,poi".to_string()),
+            ],
+        }
+    }
+}
+
+/// The region that place searches are restricted to or biased towards.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SearchRegion {
+    /// search the whole world without any preference
+    None,
+    /// only return places within the given countries (ISO 3166-1 alpha-2 codes)
+    Countries(Vec<String>),
+    /// prefer, but do not restrict to, places within the box spanned by two opposite corners
+    BoundingBox(Coordinates, Coordinates),
+}
+impl SearchRegion {
+    /// Checks whether the `place` lies within this region. Every place lies within [SearchRegion::None].
+    pub fn contains(&self, place: &Place) -> bool {
+        match self {
+            SearchRegion::None => true,
+            SearchRegion::Countries(codes) => place.address.country_code.as_ref()
+                .is_some_and(|code| codes.contains(&code.to_lowercase())),
+            SearchRegion::BoundingBox(first, second) => {
+                let Some(position) = Option::<Coordinates>::from(place) else {
+                    return false;
+                };
+                let within = |value: f64, a: f64, b: f64| a.min(b) <= value && value <= a.max(b);
+
+                within(position.latitude, first.latitude, second.latitude)
+                    && within(position.longitude, first.longitude, second.longitude)
+            }
+        }
+    }
+
+    fn search_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            SearchRegion::None => vec![],
+            SearchRegion::Countries(codes) => vec![("countrycodes", codes.join(","))],
+            // the viewbox expects the corners as <lon1>,<lat1>,<lon2>,<lat2>
+            SearchRegion::BoundingBox(first, second) => vec![("viewbox", format!("{},{},{},{}",
+                first.longitude, first.latitude, second.longitude, second.latitude))],
+        }
+    }
+}
+impl Default for SearchRegion {
+    // roughly the area of central Europe around Austria
+    fn default() -> Self {
+        SearchRegion::BoundingBox(Coordinates::new(55.030541, 5.324132), Coordinates::new(45.850230, 17.435780))
+    }
+}
+impl fmt::Display for SearchRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchRegion::None => write!(f, "none"),
+            SearchRegion::Countries(codes) => write!(f, "{}", codes.join(", ").to_uppercase()),
+            SearchRegion::BoundingBox(first, second) => write!(f, "{}; {}", first, second),
+        }
+    }
+}
+impl FromStr for SearchRegion {
+    type Err = SearchRegionError;
+
+    /// Parses either `none`, a list of country codes like `AT, DE` or a bounding box given by two
+    /// corners separated by a semicolon like `55.03, 5.32; 45.85, 17.43`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.eq_ignore_ascii_case("none") {
+            return Ok(SearchRegion::None);
+        }
+
+        if let Some((first, second)) = s.split_once(';') {
+            return match (parsing::parse_coordinates(first), parsing::parse_coordinates(second)) {
+                (Some(first), Some(second)) => Ok(SearchRegion::BoundingBox(first, second)),
+                _ => Err(SearchRegionError::Invalid),
+            };
+        }
+
+        let codes: Vec<String> = s.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|code| !code.is_empty())
+            .map(|code| code.to_lowercase())
+            .collect();
+
+        let is_country_code = |code: &String| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic());
+        if codes.is_empty() || !codes.iter().all(is_country_code) {
+            return Err(SearchRegionError::Invalid);
+        }
+
+        // Nominatim silently finds nothing in countries that don't exist
+        let unknown: Vec<String> = codes.iter()
+            .filter(|code| !place_query::is_country_code(code))
+            .map(|code| code.to_uppercase())
+            .collect();
+        match unknown.is_empty() {
+            true => Ok(SearchRegion::Countries(codes)),
+            false => Err(SearchRegionError::UnknownCountries(unknown)),
+        }
+    }
+}
+
+/// Why a [SearchRegion] could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchRegionError {
+    /// neither `none`, country codes nor a bounding box
+    Invalid,
+    /// two-letter codes which are no ISO 3166-1 alpha-2 codes, in uppercase
+    UnknownCountries(Vec<String>),
 }
 
 type NominatimResult = Vec<Place>;
@@ -265,6 +626,17 @@ impl From<NominatimError> for ApiError {
     }
 }
 
+// the reverse endpoint reports coordinates without any address (e.g. in the ocean) in this format
+#[derive(Deserialize, Debug, Clone)]
+pub struct NominatimReverseError {
+    pub error: String,
+}
+impl From<NominatimReverseError> for ApiError {
+    fn from(_: NominatimReverseError) -> Self {
+        ApiError::NotFound
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct NominatimErrorDetails {
     pub reason: String,
@@ -273,16 +645,95 @@ pub struct NominatimErrorDetails {
 
 // --------------------- functions --------------------
 
+// only the public instance is subject to the usage policy, self-hosted instances may be queried freely
+async fn rate_limit(host: &str, requester: Requester) -> Result<(), ApiError> {
+    if host == PUBLIC_HOST {
+        RATE_LIMITER.acquire(requester).await?;
+    }
+    Ok(())
+}
+
+fn endpoint(host: &str, path: &str) -> String {
+    format!("{}{}", host.trim_end_matches('/'), path)
+}
+
 // Cache up to 200 place requests and their responses (result = true -> only cache Ok variants)
 #[cached(
-    ty = "SizedCache<String, Vec<Place>>",
+    ty = "SizedCache<(String, PlaceQuery, FeatureKind, SearchRegion, String), Vec<Place>>",
+    create = "{ SizedCache::with_size(CACHED_ITEMS) }",
+    convert = r#"{ (host.to_string(), query.clone(), kind, region.clone(), language.to_string()) }"#,
+    result = true
+)]
+pub async fn query_place(client: &reqwest::Client, requester: Requester, host: &str, query: &PlaceQuery,
+                         kind: FeatureKind, region: &SearchRegion, language: &str) -> Result<Vec<Place>, ApiError>
+{
+    rate_limit(host, requester).await?;
+
+    let mut params = kind.search_params(query);
+    params.extend(region.search_params());
+    params.push(("accept-language", language.to_string()));
+
+    let places = common::query_api::<Vec<Place>, NominatimResult, ClimateApiError>
+        (client, &endpoint(host, SEARCH_PATH), params).await?;
+
+    remember_places(host, &places, language).await;
+
+    // a free-form search also matches shops, streets etc. named after the feature, so drop those
+    Ok(match kind {
+        FeatureKind::Settlement => places,
+        FeatureKind::Natural => places.into_iter().filter(Place::is_natural_feature).collect(),
+    })
+}
+
+/// Looks up a place by the short form of its OSM object, see [Place::osm_reference].
+#[cached(
+    ty = "SizedCache<(String, String, String), Vec<Place>>",
     create = "{ SizedCache::with_size(CACHED_ITEMS) }",
-    convert = r#"{ name.to_string() }"#,
+    convert = r#"{ (host.to_string(), reference.to_string(), language.to_string()) }"#,
     result = true
 )]
-pub async fn query_place(client: &reqwest::Client, name: &str) -> Result<Vec<Place>, ApiError> {
-    let params = [("city", name.to_string())];
+pub async fn lookup_place(client: &reqwest::Client, requester: Requester, host: &str, reference: &str, language: &str)
+    -> Result<Vec<Place>, ApiError>
+{
+    rate_limit(host, requester).await?;
+
+    let params = [
+        ("osm_ids", reference.to_string()),
+        ("accept-language", language.to_string()),
+    ];
 
     common::query_api::<Vec<Place>, NominatimResult, ClimateApiError>
-        (client, BASE_URL, params).await
-}
\ No newline at end of file
+        (client, &endpoint(host, LOOKUP_PATH), params).await
+}
+
+// places found by a search are likely to be looked up by their reference soon (e.g. after autocompletion)
+async fn remember_places(host: &str, places: &[Place], language: &str) {
+    let mut cache = LOOKUP_PLACE.lock().await;
+
+    for place in places {
+        if let Some(reference) = place.osm_reference() {
+            cache.cache_set((host.to_string(), reference, language.to_string()), vec![place.clone()]);
+        }
+    }
+}
+
+#[cached(
+    ty = "SizedCache<(String, Coordinates, String), Place>",
+    create = "{ SizedCache::with_size(CACHED_ITEMS) }",
+    convert = r#"{ (host.to_string(), coordinates.clone(), language.to_string()) }"#,
+    result = true
+)]
+pub async fn reverse_place(client: &reqwest::Client, requester: Requester, host: &str, coordinates: &Coordinates,
+                           language: &str) -> Result<Place, ApiError>
+{
+    rate_limit(host, requester).await?;
+
+    let params = [
+        ("lat", coordinates.latitude.to_string()),
+        ("lon", coordinates.longitude.to_string()),
+        ("accept-language", language.to_string()),
+    ];
+
+    common::query_api::<Place, Place, NominatimReverseError>
+        (client, &endpoint(host, REVERSE_PATH), params).await
+}
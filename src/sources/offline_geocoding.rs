@@ -0,0 +1,331 @@
+//! Searches places in a bundled extract of the GeoNames database, without any network requests.
+//!
+//! The data files are embedded at compile time and use the formats of the GeoNames dumps
+//! (<https://download.geonames.org/export/dump/>):
+//! - `data/geonames_cities.txt` - one of the `citiesXXX.txt` extracts
+//! - `data/geonames_admin1.txt` - `admin1CodesASCII.txt`, to resolve the names of states
+//! - `data/geonames_alternate_names.txt` - `alternateNamesV2.txt`, ideally reduced to the lines of
+//!   the languages in [NAME_LANGUAGES] and the places of the cities extract to keep the binary small
+//!
+//! How to obtain the files is described at the `offline-geocoding` feature in `Cargo.toml`.
+use super::common::Coordinates;
+use super::nominatim::{Address, AddressLevel, Extratags, FeatureKind, Place, SearchRegion};
+use super::nominatim::{GEONAMES_CATEGORY, GEONAMES_REFERENCE_PREFIX};
+use super::place_query::PlaceQuery;
+use crate::utils::text;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+/// Languages whose alternate names are indexed and shown as translations of the place name.
+const NAME_LANGUAGES: [&str; 2] = ["de", "en"];
+const MAX_RESULTS: usize = 10;
+// only search for typos in longer names, since short ones match too many other places
+const MIN_FUZZY_LENGTH: usize = 4;
+
+static INDEX: OnceLock<PlaceIndex> = OnceLock::new();
+
+struct Entry {
+    id: i64,
+    name: String,
+    coordinates: Coordinates,
+    feature_code: String,
+    country_code: String,
+    state: Option<String>,
+    population: u64,
+    elevation: Option<i32>,
+    translations: HashMap<&'static str, String>,
+}
+
+struct PlaceIndex {
+    entries: Vec<Entry>,
+    ids: HashMap<i64, usize>,
+    // normalized names (including alternate names) pointing to the entries they belong to
+    names: BTreeMap<String, Vec<usize>>,
+}
+
+// earlier variants are better matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Match {
+    Exact,
+    Prefix,
+    Fuzzy,
+}
+
+fn get_index() -> &'static PlaceIndex {
+    INDEX.get_or_init(|| {
+        // Hard-coded relative paths are fine here, since they are evaluated at compile time and thus
+        // throw an error during compilation if a file is not found.
+        PlaceIndex::build(
+            include_str!("../../data/geonames_cities.txt"),
+            include_str!("../../data/geonames_admin1.txt"),
+            include_str!("../../data/geonames_alternate_names.txt"),
+        )
+    })
+}
+
+pub fn init() {
+    get_index(); // force building the index at startup instead of during the first search
+}
+
+impl PlaceIndex {
+    fn build(cities: &str, admin1_codes: &str, alternate_names: &str) -> PlaceIndex {
+        // e.g. "AT.06\tStyria\tStyria\t2764581"
+        let states: HashMap<&str, &str> = admin1_codes.lines()
+            .filter_map(|line| {
+                let mut columns = line.split('\t');
+                Some((columns.next()?, columns.next()?))
+            })
+            .collect();
+
+        let mut entries: Vec<Entry> = cities.lines()
+            .filter_map(|line| Self::parse_city(line, &states))
+            .collect();
+
+        let ids: HashMap<i64, usize> = entries.iter().enumerate()
+            .map(|(idx, entry)| (entry.id, idx))
+            .collect();
+
+        for line in alternate_names.lines() {
+            let columns: Vec<&str> = line.split('\t').collect();
+            // alternateNameId, geonameid, isolanguage, name, isPreferredName, isShortName, isColloquial, isHistoric
+            let [_, id, language, name, preferred, _, colloquial, historic, ..] = columns.as_slice() else {
+                continue;
+            };
+            let (Some(language), Ok(id)) = (NAME_LANGUAGES.iter().find(|l| *l == language), id.parse::<i64>()) else {
+                continue;
+            };
+            if *colloquial == "1" || *historic == "1" {
+                continue;
+            }
+
+            if let Some(&idx) = ids.get(&id) {
+                let translations = &mut entries[idx].translations;
+                // preferred names replace whatever was found before
+                if *preferred == "1" || !translations.contains_key(language) {
+                    translations.insert(language, name.to_string());
+                }
+            }
+        }
+
+        let mut names: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (idx, entry) in entries.iter().enumerate() {
+            for name in std::iter::once(&entry.name).chain(entry.translations.values()) {
+                let indices = names.entry(text::normalize(name)).or_default();
+                if !indices.contains(&idx) {
+                    indices.push(idx);
+                }
+            }
+        }
+
+        PlaceIndex { entries, ids, names }
+    }
+
+    fn parse_city(line: &str, states: &HashMap<&str, &str>) -> Option<Entry> {
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() < 17 {
+            return None;
+        }
+
+        let state_key = format!("{}.{}", columns[8], columns[10]);
+
+        Some(Entry {
+            id: columns[0].parse().ok()?,
+            name: columns[1].to_string(),
+            coordinates: Coordinates::new(columns[4].parse().ok()?, columns[5].parse().ok()?),
+            feature_code: columns[7].to_string(),
+            country_code: columns[8].to_lowercase(),
+            state: states.get(state_key.as_str()).map(|state| state.to_string()),
+            population: columns[14].parse().unwrap_or(0),
+            // the elevation column is often empty, but the digital elevation model always has a value
+            elevation: columns[15].parse().or_else(|_| columns[16].parse()).ok(),
+            translations: HashMap::new(),
+        })
+    }
+
+    fn search(&self, name: &str, kind: FeatureKind, region: &SearchRegion) -> Vec<&Entry> {
+        let query = text::normalize(name);
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let mut matches: HashMap<usize, Match> = HashMap::new();
+        let add_matches = |matches: &mut HashMap<usize, Match>, indices: &Vec<usize>, quality: Match| {
+            for &idx in indices {
+                let best = matches.entry(idx).or_insert(quality);
+                *best = (*best).min(quality);
+            }
+        };
+
+        for (key, indices) in self.names.range(query.clone()..).take_while(|(key, _)| key.starts_with(&query)) {
+            add_matches(&mut matches, indices, if *key == query { Match::Exact } else { Match::Prefix });
+        }
+
+        // only resort to typo tolerance if there are not enough proper matches
+        let query_length = query.chars().count();
+        if matches.len() < MAX_RESULTS && query_length >= MIN_FUZZY_LENGTH {
+            let max_distance = if query_length < 8 { 1 } else { 2 };
+
+            for (key, indices) in &self.names {
+                if key.chars().count().abs_diff(query_length) <= max_distance
+                    && text::edit_distance(key, &query) <= max_distance
+                {
+                    add_matches(&mut matches, indices, Match::Fuzzy);
+                }
+            }
+        }
+
+        let mut results: Vec<(Match, &Entry)> = matches.into_iter()
+            .map(|(idx, quality)| (quality, &self.entries[idx]))
+            .filter(|(_, entry)| entry.matches_kind(kind) && entry.matches_region(region))
+            .collect();
+
+        // the more people live in a place, the more likely it is the one the user is looking for
+        results.sort_by(|(quality_a, a), (quality_b, b)| quality_a.cmp(quality_b)
+            .then(b.population.cmp(&a.population)));
+
+        results.into_iter()
+            .take(MAX_RESULTS)
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    fn lookup(&self, reference: &str) -> Option<&Entry> {
+        let id: i64 = reference.strip_prefix(GEONAMES_REFERENCE_PREFIX)?.parse().ok()?;
+        self.ids.get(&id).map(|&idx| &self.entries[idx])
+    }
+}
+
+impl Entry {
+    fn matches_kind(&self, kind: FeatureKind) -> bool {
+        match kind {
+            // all populated places have feature codes starting with PPL
+            FeatureKind::Settlement => self.feature_code.starts_with("PPL"),
+            FeatureKind::Natural => !self.feature_code.starts_with("PPL"),
+        }
+    }
+
+    fn matches_region(&self, region: &SearchRegion) -> bool {
+        match region {
+            SearchRegion::Countries(codes) => codes.contains(&self.country_code),
+            // a bounding box is only a preference, not a restriction
+            _ => true,
+        }
+    }
+
+    fn to_place(&self) -> Place {
+        let address_type = if self.feature_code.starts_with("PPL") {
+            AddressLevel::Municipality
+        } else {
+            AddressLevel::Other(self.feature_code.to_lowercase())
+        };
+
+        let mut address = Address::default()
+            .with_level(&AddressLevel::State, self.state.clone())
+            .with_level(&address_type, Some(self.name.clone()));
+        address.country_code = Some(self.country_code.clone());
+
+        let mut place = Place::new(self.id, &self.coordinates, &self.name, address_type, address);
+        place.category = GEONAMES_CATEGORY.to_string();
+        place.kind = self.feature_code.clone();
+        place.extratags = Some(Extratags {
+            population: Some(self.population.to_string()),
+            ele: self.elevation.map(|e| e.to_string()),
+            ..Default::default()
+        });
+
+        for (language, translation) in &self.translations {
+            place.name.add_translation(language, translation.clone());
+        }
+
+        place
+    }
+}
+
+/// Searches the bundled GeoNames extract for places with a name similar to the one of the `query`.
+/// Exact matches are listed before prefix matches and matches with typos, places with a larger
+/// population first. The extract contains no postal codes, so only the name and region are considered.
+pub fn query_place(query: &PlaceQuery, kind: FeatureKind, region: &SearchRegion) -> Vec<Place> {
+    get_index().search(&query.name, kind, region).into_iter()
+        .map(Entry::to_place)
+        .collect()
+}
+
+/// Returns the place with the given reference like `G2778067`, see [Place::reference].
+pub fn lookup_place(reference: &str) -> Option<Place> {
+    get_index().lookup(reference).map(Entry::to_place)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CITIES: &str = "\
+2778067\tGraz\tGraz\tGratz,Graz\t47.06667\t15.45\tP\tPPLA\tAT\t\t06\t\t\t\t222326\t\t353\tEurope/Vienna\t2019-09-05
+2761369\tVienna\tVienna\t\t48.20849\t16.37208\tP\tPPLC\tAT\t\t09\t\t\t\t1691468\t\t171\tEurope/Vienna\t2019-09-05
+4791259\tVienna\tVienna\t\t38.90122\t-77.26526\tP\tPPL\tUS\t\tVA\t\t\t\t16489\t\t109\tAmerica/New_York\t2011-05-14
+2778046\tGratkorn\tGratkorn\t\t47.13333\t15.35\tP\tPPL\tAT\t\t06\t\t\t\t7692\t389\t386\tEurope/Vienna\t2011-04-25
+2779343\tGrossglockner\tGrossglockner\t\t47.07453\t12.69372\tT\tMT\tAT\t\t07\t\t\t\t0\t3798\t3749\tEurope/Vienna\t2012-01-19
+";
+    const ADMIN1_CODES: &str = "AT.06\tStyria\tStyria\t2764581\nAT.09\tVienna\tVienna\t2761367\n";
+    const ALTERNATE_NAMES: &str = "\
+1\t2761369\tde\tWien\t1\t\t\t\t\t
+2\t2761369\ten\tVienna\t1\t\t\t\t\t
+3\t2761369\tde\tWienn\t\t\t\t1\t\t
+4\t2779343\tde\tGroßglockner\t1\t\t\t\t\t
+";
+
+    fn index() -> PlaceIndex {
+        PlaceIndex::build(CITIES, ADMIN1_CODES, ALTERNATE_NAMES)
+    }
+
+    fn search(name: &str, kind: FeatureKind, region: &SearchRegion) -> Vec<i64> {
+        index().search(name, kind, region).into_iter().map(|entry| entry.id).collect()
+    }
+
+    #[test]
+    fn exact_matches_before_prefix_matches() {
+        assert_eq!(search("Graz", FeatureKind::Settlement, &SearchRegion::None), vec![2778067]);
+        assert_eq!(search("gra", FeatureKind::Settlement, &SearchRegion::None), vec![2778067, 2778046]);
+    }
+
+    #[test]
+    fn larger_population_first() {
+        assert_eq!(search("Vienna", FeatureKind::Settlement, &SearchRegion::None), vec![2761369, 4791259]);
+    }
+
+    #[test]
+    fn translated_names() {
+        assert_eq!(search("wien", FeatureKind::Settlement, &SearchRegion::None), vec![2761369]);
+        // historic names are neither indexed nor shown
+        assert!(!index().names.contains_key("wienn"));
+    }
+
+    #[test]
+    fn typos_in_long_names_only() {
+        assert_eq!(search("Viena", FeatureKind::Settlement, &SearchRegion::None), vec![2761369, 4791259]);
+        assert!(search("Grz", FeatureKind::Settlement, &SearchRegion::None).is_empty());
+    }
+
+    #[test]
+    fn kind_and_region() {
+        assert_eq!(search("Großglockner", FeatureKind::Natural, &SearchRegion::None), vec![2779343]);
+        assert!(search("Grossglockner", FeatureKind::Settlement, &SearchRegion::None).is_empty());
+
+        let region = SearchRegion::Countries(vec!["us".to_string()]);
+        assert_eq!(search("Vienna", FeatureKind::Settlement, &region), vec![4791259]);
+    }
+
+    #[test]
+    fn lookup() {
+        let index = index();
+        let entry = index.lookup("G2778067").unwrap();
+        assert_eq!(entry.name, "Graz");
+        assert_eq!(entry.state.as_deref(), Some("Styria"));
+        assert_eq!(entry.elevation, Some(353));
+        assert_eq!(entry.country_code, "at");
+
+        assert_eq!(index.lookup("G2761369").unwrap().translations.get("de").map(String::as_str), Some("Wien"));
+        assert!(index.lookup("G1").is_none());
+        assert!(index.lookup("N2778067").is_none());
+    }
+}
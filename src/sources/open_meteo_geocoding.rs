@@ -0,0 +1,119 @@
+use super::common::*;
+use super::nominatim::{Address, AddressLevel, Extratags, FeatureKind, Place, SearchRegion};
+use super::place_query::PlaceQuery;
+use crate::sources::common;
+use cached::proc_macro::cached;
+use cached::SizedCache;
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://geocoding-api.open-meteo.com/v1/search?count=10&format=json";
+const CACHED_ITEMS: usize = 200;
+// GeoNames feature codes of mountains, passes and huts
+const NATURAL_FEATURE_CODES: [&str; 6] = ["PK", "PKS", "MT", "MTS", "PASS", "HUT"];
+
+#[derive(Deserialize, Debug)]
+struct GeocodingResult {
+    // the key is missing entirely if nothing was found
+    #[serde(default)]
+    results: Vec<Location>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Location {
+    id: i64,
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    elevation: Option<f32>,
+    feature_code: Option<String>,
+    country_code: Option<String>,
+    country: Option<String>,
+    admin1: Option<String>,
+    admin2: Option<String>,
+    admin3: Option<String>,
+    population: Option<u64>,
+    #[serde(default)]
+    postcodes: Vec<String>,
+}
+impl Location {
+    fn matches_kind(&self, kind: FeatureKind) -> bool {
+        let code = self.feature_code.as_deref().unwrap_or_default();
+
+        match kind {
+            // all populated places have feature codes starting with PPL
+            FeatureKind::Settlement => code.starts_with("PPL"),
+            FeatureKind::Natural => NATURAL_FEATURE_CODES.contains(&code),
+        }
+    }
+
+    // states are not compared, since their names are localized and rarely match the user's spelling
+    fn matches_postcode(&self, query: &PlaceQuery) -> bool {
+        // settlements without any known postal codes are not ruled out
+        query.postcode.as_ref().is_none_or(|postcode| self.postcodes.is_empty() || self.postcodes.contains(postcode))
+    }
+}
+impl From<Location> for Place {
+    fn from(location: Location) -> Place {
+        let address_type = match location.feature_code.as_deref() {
+            Some(code) if code.starts_with("PPL") => AddressLevel::Municipality,
+            Some(code) => AddressLevel::Other(code.to_lowercase()),
+            None => AddressLevel::Other(String::new()),
+        };
+
+        // GeoNames' first order administrative division is a state, the second one a county
+        let mut address = Address::default()
+            .with_level(&AddressLevel::State, location.admin1)
+            .with_level(&AddressLevel::County, location.admin2.or(location.admin3))
+            .with_level(&AddressLevel::Country, location.country)
+            .with_level(&address_type, Some(location.name.clone()));
+        address.postcode = location.postcodes.into_iter().next();
+        address.country_code = location.country_code.map(|code| code.to_lowercase());
+
+        let mut place = Place::new(location.id, &Coordinates::new(location.latitude, location.longitude),
+                                   &location.name, address_type, address);
+        place.extratags = Some(Extratags {
+            population: location.population.map(|p| p.to_string()),
+            ele: location.elevation.map(|e| e.to_string()),
+            ..Default::default()
+        });
+
+        place
+    }
+}
+
+#[cached(
+    ty = "SizedCache<(PlaceQuery, FeatureKind, SearchRegion, String), Vec<Place>>",
+    create = "{ SizedCache::with_size(CACHED_ITEMS) }",
+    convert = r#"{ (query.clone(), kind, region.clone(), language.to_string()) }"#,
+    result = true
+)]
+pub async fn query_place(client: &reqwest::Client, query: &PlaceQuery, kind: FeatureKind, region: &SearchRegion,
+                         language: &str) -> Result<Vec<Place>, ApiError>
+{
+    // the API only searches by name, so the postal code is checked afterwards
+    let mut params = vec![
+        ("name", query.name.clone()),
+        ("language", language.to_string()),
+    ];
+
+    // the API can only restrict the results to a single country
+    if let SearchRegion::Countries(codes) = region {
+        if let [code] = codes.as_slice() {
+            params.push(("countryCode", code.to_uppercase()));
+        }
+    }
+
+    let result = common::query_api::<GeocodingResult, GeocodingResult, ClimateApiError>
+        (client, BASE_URL, params).await?;
+
+    Ok(result.results.into_iter()
+        .filter(|location| location.matches_kind(kind))
+        .filter(|location| match region {
+            SearchRegion::Countries(codes) => location.country_code.as_ref()
+                .is_some_and(|code| codes.contains(&code.to_lowercase())),
+            _ => true,
+        })
+        .filter(|location| location.matches_postcode(query))
+        .map(Place::from)
+        .collect())
+}
@@ -0,0 +1,144 @@
+use super::common::*;
+use super::nominatim::{Address, AddressLevel, FeatureKind, Place, SearchRegion};
+use super::place_query::PlaceQuery;
+use crate::sources::common;
+use cached::proc_macro::cached;
+use cached::SizedCache;
+use serde::Deserialize;
+
+pub const PUBLIC_HOST: &str = "https://photon.komoot.io";
+const SEARCH_PATH: &str = "/api/?limit=10";
+const CACHED_ITEMS: usize = 200;
+// photon returns names in the local language unless one of these is requested
+const SUPPORTED_LANGUAGES: [&str; 3] = ["de", "en", "fr"];
+// place types that count as settlements
+const SETTLEMENT_TAGS: [&str; 6] = ["city", "town", "village", "hamlet", "municipality", "isolated_dwelling"];
+
+#[derive(Deserialize, Debug)]
+struct PhotonResult {
+    features: Vec<Feature>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Feature {
+    geometry: Geometry,
+    properties: Properties,
+}
+
+#[derive(Deserialize, Debug)]
+struct Geometry {
+    // GeoJSON positions are ordered as [longitude, latitude]
+    coordinates: (f64, f64),
+}
+
+#[derive(Deserialize, Debug)]
+struct Properties {
+    osm_id: i64,
+    osm_type: String,
+    osm_key: String,
+    osm_value: String,
+    name: Option<String>,
+    postcode: Option<String>,
+    district: Option<String>,
+    city: Option<String>,
+    county: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+    #[serde(rename = "countrycode")]
+    country_code: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PhotonError {
+    pub message: String,
+}
+impl From<PhotonError> for ApiError {
+    fn from(error: PhotonError) -> Self {
+        ApiError::BadRequest { reason: error.message }
+    }
+}
+
+impl From<Feature> for Option<Place> {
+    fn from(feature: Feature) -> Option<Place> {
+        let properties = feature.properties;
+        let name = properties.name?;
+        let (longitude, latitude) = feature.geometry.coordinates;
+        let address_type = AddressLevel::from_tag(&properties.osm_value);
+
+        // the feature itself is not part of its address properties, so add it at its own level
+        let mut address = Address::default()
+            .with_level(&AddressLevel::District, properties.district)
+            .with_level(&AddressLevel::Municipality, properties.city)
+            .with_level(&AddressLevel::County, properties.county)
+            .with_level(&AddressLevel::State, properties.state)
+            .with_level(&AddressLevel::Country, properties.country)
+            .with_level(&address_type, Some(name.clone()));
+        address.postcode = properties.postcode;
+        address.country_code = properties.country_code.map(|code| code.to_lowercase());
+
+        let mut place = Place::new(properties.osm_id, &Coordinates::new(latitude, longitude), &name,
+                                   address_type, address);
+        place.category = properties.osm_key;
+        place.kind = properties.osm_value;
+        place.osm_type = Some(properties.osm_type);
+        place.osm_id = Some(properties.osm_id);
+
+        Some(place)
+    }
+}
+impl From<PhotonResult> for Vec<Place> {
+    fn from(result: PhotonResult) -> Self {
+        result.features.into_iter()
+            .filter_map(Option::<Place>::from)
+            .collect()
+    }
+}
+
+fn search_params(query: &PlaceQuery, kind: FeatureKind, region: &SearchRegion, language: &str)
+    -> Vec<(&'static str, String)>
+{
+    let mut params = vec![("q", query.free_form())];
+
+    if SUPPORTED_LANGUAGES.contains(&language) {
+        params.push(("lang", language.to_string()));
+    }
+
+    // multiple tag filters are combined with a logical OR
+    match kind {
+        FeatureKind::Settlement => params.extend(SETTLEMENT_TAGS.iter()
+            .map(|value| ("osm_tag", format!("place:{}", value)))),
+        FeatureKind::Natural => params.extend(FeatureKind::NATURAL_TAGS.iter()
+            .map(|(key, value)| ("osm_tag", format!("{}:{}", key, value)))),
+    }
+
+    // photon only supports biasing the results towards a single location
+    if let SearchRegion::BoundingBox(first, second) = region {
+        params.push(("lat", ((first.latitude + second.latitude) / 2.0).to_string()));
+        params.push(("lon", ((first.longitude + second.longitude) / 2.0).to_string()));
+    }
+
+    params
+}
+
+#[cached(
+    ty = "SizedCache<(String, PlaceQuery, FeatureKind, SearchRegion, String), Vec<Place>>",
+    create = "{ SizedCache::with_size(CACHED_ITEMS) }",
+    convert = r#"{ (host.to_string(), query.clone(), kind, region.clone(), language.to_string()) }"#,
+    result = true
+)]
+pub async fn query_place(client: &reqwest::Client, host: &str, query: &PlaceQuery, kind: FeatureKind,
+                         region: &SearchRegion, language: &str)
+    -> Result<Vec<Place>, ApiError>
+{
+    let url = format!("{}{}", host.trim_end_matches('/'), SEARCH_PATH);
+    let places = common::query_api::<Vec<Place>, PhotonResult, PhotonError>
+        (client, &url, search_params(query, kind, region, language)).await?;
+
+    // photon cannot filter by country, so restrict the results afterwards
+    Ok(match region {
+        SearchRegion::Countries(codes) => places.into_iter()
+            .filter(|place| place.address.country_code.as_ref().is_some_and(|code| codes.contains(code)))
+            .collect(),
+        _ => places,
+    })
+}
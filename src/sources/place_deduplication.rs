@@ -0,0 +1,120 @@
+//! Merges search results that describe the same real place, e.g. a town returned both as its boundary
+//! relation and as its centre node, so that only actual alternatives are offered to the user.
+use super::common::Coordinates;
+use super::nominatim::Place;
+use crate::utils::text;
+
+// a boundary's centroid may lie a bit away from the node marking the town centre
+const MAX_DUPLICATE_DISTANCE_KM: f64 = 2.0;
+
+/// Removes near-identical places from the list. Places are duplicates if they share their name and lie
+/// at the same coordinates (compared in thousandths of a degree) or very close to each other. Of every
+/// group of duplicates, the most informative place is kept at the position of the first one.
+pub fn deduplicate(places: Vec<Place>) -> Vec<Place> {
+    let mut clusters: Vec<Vec<Place>> = Vec::new();
+
+    for place in places {
+        match clusters.iter_mut().find(|cluster| is_duplicate(&cluster[0], &place)) {
+            Some(cluster) => cluster.push(place),
+            None => clusters.push(vec![place]),
+        }
+    }
+
+    clusters.into_iter()
+        .filter_map(merge)
+        .collect()
+}
+
+fn is_duplicate(a: &Place, b: &Place) -> bool {
+    if text::normalize(&a.name.local) != text::normalize(&b.name.local) {
+        return false;
+    }
+
+    match (Option::<Coordinates>::from(a), Option::<Coordinates>::from(b)) {
+        (Some(position_a), Some(position_b)) => position_a == position_b
+            || position_a.distance_km(&position_b) <= MAX_DUPLICATE_DISTANCE_KM,
+        _ => false,
+    }
+}
+
+// keep the most informative place, but don't lose how important the others considered it
+fn merge(cluster: Vec<Place>) -> Option<Place> {
+    let importance = cluster.iter().map(|place| place.importance).fold(0.0, f32::max);
+
+    let mut best = cluster.into_iter()
+        .enumerate()
+        // prefer the earlier place on a tie, since the backend ranked it higher
+        .max_by_key(|(idx, place)| (information(place), std::cmp::Reverse(*idx)))
+        .map(|(_, place)| place)?;
+
+    best.importance = importance;
+    Some(best)
+}
+
+// the number of details known about a place
+fn information(place: &Place) -> usize {
+    let tags = place.extratags.as_ref().map_or(0, |tags| [
+        &tags.wikidata, &tags.wikipedia, &tags.website, &tags.capital, &tags.population, &tags.population_date, &tags.ele,
+    ].iter().filter(|tag| tag.is_some()).count());
+
+    tags + place.name.translations().len() + usize::from(place.address.postcode.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::nominatim::{Address, AddressLevel, Extratags};
+
+    fn place(id: i64, name: &str, latitude: f64, longitude: f64, importance: f32) -> Place {
+        let mut place = Place::new(id, &Coordinates::new(latitude, longitude), name, AddressLevel::Municipality,
+                                   Address::default());
+        place.importance = importance;
+        place
+    }
+
+    fn ids(places: &[Place]) -> Vec<i64> {
+        places.iter().map(|place| place.id).collect()
+    }
+
+    #[test]
+    fn merges_boundary_and_centre() {
+        let boundary = place(1, "Graz", 47.0708, 15.4386, 0.3);
+        let mut centre = place(2, "graz", 47.0667, 15.45, 0.6);
+        centre.extratags = Some(Extratags { wikidata: Some("Q13298".to_string()), ..Default::default() });
+        let other = place(3, "Graz-Umgebung", 47.1, 15.4, 0.2);
+
+        let deduplicated = deduplicate(vec![boundary, other, centre]);
+        // the more informative centre takes the place of the boundary and keeps the higher importance
+        assert_eq!(ids(&deduplicated), vec![2, 3]);
+        assert_eq!(deduplicated[0].importance, 0.6);
+    }
+
+    #[test]
+    fn keeps_distant_places_of_the_same_name() {
+        let places = vec![place(1, "Springfield", 39.8, -89.65, 0.5), place(2, "Springfield", 37.2, -93.3, 0.5)];
+        assert_eq!(ids(&deduplicate(places)), vec![1, 2]);
+    }
+
+    #[test]
+    fn keeps_different_names_at_the_same_position() {
+        let places = vec![place(1, "Graz", 47.07, 15.44, 0.5), place(2, "Innere Stadt", 47.07, 15.44, 0.5)];
+        assert_eq!(ids(&deduplicate(places)), vec![1, 2]);
+    }
+
+    #[test]
+    fn prefers_the_earlier_place_on_a_tie() {
+        let places = vec![place(1, "Graz", 47.07, 15.44, 0.5), place(2, "Graz", 47.07, 15.44, 0.7)];
+        let deduplicated = deduplicate(places);
+        assert_eq!(ids(&deduplicated), vec![1]);
+        assert_eq!(deduplicated[0].importance, 0.7);
+    }
+
+    #[test]
+    fn never_merges_places_without_coordinates() {
+        let mut first = place(1, "Graz", 0.0, 0.0, 0.5);
+        let mut second = place(2, "Graz", 0.0, 0.0, 0.5);
+        first.lat = String::new();
+        second.lat = String::new();
+        assert_eq!(ids(&deduplicate(vec![first, second])), vec![1, 2]);
+    }
+}
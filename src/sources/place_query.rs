@@ -0,0 +1,271 @@
+use super::nominatim::SearchRegion;
+use crate::utils::text;
+use std::fmt;
+
+// names of frequently searched countries in English and German, normalized
+const COUNTRY_NAMES: [(&str, &str); 52] = [
+    ("austria", "at"), ("osterreich", "at"),
+    ("germany", "de"), ("deutschland", "de"),
+    ("switzerland", "ch"), ("schweiz", "ch"),
+    ("liechtenstein", "li"),
+    ("italy", "it"), ("italien", "it"),
+    ("france", "fr"), ("frankreich", "fr"),
+    ("slovenia", "si"), ("slowenien", "si"),
+    ("hungary", "hu"), ("ungarn", "hu"),
+    ("slovakia", "sk"), ("slowakei", "sk"),
+    ("czechia", "cz"), ("czech republic", "cz"), ("tschechien", "cz"),
+    ("poland", "pl"), ("polen", "pl"),
+    ("croatia", "hr"), ("kroatien", "hr"),
+    ("netherlands", "nl"), ("niederlande", "nl"),
+    ("belgium", "be"), ("belgien", "be"),
+    ("luxembourg", "lu"), ("luxemburg", "lu"),
+    ("spain", "es"), ("spanien", "es"),
+    ("portugal", "pt"),
+    ("united kingdom", "gb"), ("uk", "gb"), ("grossbritannien", "gb"),
+    ("ireland", "ie"), ("irland", "ie"),
+    ("denmark", "dk"), ("danemark", "dk"),
+    ("sweden", "se"), ("schweden", "se"),
+    ("norway", "no"), ("norwegen", "no"),
+    ("finland", "fi"), ("finnland", "fi"),
+    ("greece", "gr"), ("griechenland", "gr"),
+    ("united states", "us"), ("usa", "us"),
+    ("canada", "ca"), ("kanada", "ca"),
+];
+
+// ISO 3166-1 alpha-2 codes of all countries and territories
+const COUNTRY_CODES: [&str; 249] = [
+    "ad", "ae", "af", "ag", "ai", "al", "am", "ao", "aq", "ar", "as", "at", "au", "aw", "ax", "az", "ba", "bb", "bd", "be",
+    "bf", "bg", "bh", "bi", "bj", "bl", "bm", "bn", "bo", "bq", "br", "bs", "bt", "bv", "bw", "by", "bz", "ca", "cc", "cd",
+    "cf", "cg", "ch", "ci", "ck", "cl", "cm", "cn", "co", "cr", "cu", "cv", "cw", "cx", "cy", "cz", "de", "dj", "dk", "dm",
+    "do", "dz", "ec", "ee", "eg", "eh", "er", "es", "et", "fi", "fj", "fk", "fm", "fo", "fr", "ga", "gb", "gd", "ge", "gf",
+    "gg", "gh", "gi", "gl", "gm", "gn", "gp", "gq", "gr", "gs", "gt", "gu", "gw", "gy", "hk", "hm", "hn", "hr", "ht", "hu",
+    "id", "ie", "il", "im", "in", "io", "iq", "ir", "is", "it", "je", "jm", "jo", "jp", "ke", "kg", "kh", "ki", "km", "kn",
+    "kp", "kr", "kw", "ky", "kz", "la", "lb", "lc", "li", "lk", "lr", "ls", "lt", "lu", "lv", "ly", "ma", "mc", "md", "me",
+    "mf", "mg", "mh", "mk", "ml", "mm", "mn", "mo", "mp", "mq", "mr", "ms", "mt", "mu", "mv", "mw", "mx", "my", "mz", "na",
+    "nc", "ne", "nf", "ng", "ni", "nl", "no", "np", "nr", "nu", "nz", "om", "pa", "pe", "pf", "pg", "ph", "pk", "pl", "pm",
+    "pn", "pr", "ps", "pt", "pw", "py", "qa", "re", "ro", "rs", "ru", "rw", "sa", "sb", "sc", "sd", "se", "sg", "sh", "si",
+    "sj", "sk", "sl", "sm", "sn", "so", "sr", "ss", "st", "sv", "sx", "sy", "sz", "tc", "td", "tf", "tg", "th", "tj", "tk",
+    "tl", "tm", "tn", "to", "tr", "tt", "tv", "tw", "tz", "ua", "ug", "um", "us", "uy", "uz", "va", "vc", "ve", "vg", "vi",
+    "vn", "vu", "wf", "ws", "ye", "yt", "za", "zm", "zw",
+];
+
+/// Checks whether the `code` is an ISO 3166-1 alpha-2 code in lowercase, e.g. `at`.
+pub fn is_country_code(code: &str) -> bool {
+    COUNTRY_CODES.contains(&code)
+}
+
+/// A country qualifier of a place search.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Country {
+    /// ISO 3166-1 alpha-2 code in lowercase
+    Code(String),
+    /// a name that could not be mapped to a country code
+    Name(String),
+}
+impl Country {
+    pub fn parse(input: &str) -> Country {
+        let input = input.trim();
+        let normalized = text::normalize(input);
+
+        if let Some((_, code)) = COUNTRY_NAMES.iter().find(|(name, _)| *name == normalized) {
+            return Country::Code(code.to_string());
+        }
+
+        // other two-letter qualifiers are rather abbreviated states, like `NÖ` for Lower Austria,
+        // which must not be mistaken for Norway after normalization
+        if input.is_ascii() && is_country_code(&normalized) {
+            Country::Code(normalized)
+        } else {
+            Country::Name(input.to_string())
+        }
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Country::Code(code) => Some(code),
+            Country::Name(_) => None,
+        }
+    }
+}
+impl fmt::Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Country::Code(code) => write!(f, "{}", code.to_uppercase()),
+            Country::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A place search split into its components, e.g. `8010 Graz, Steiermark, AT`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PlaceQuery {
+    pub name: String,
+    pub postcode: Option<String>,
+    pub state: Option<String>,
+    pub country: Option<Country>,
+}
+impl PlaceQuery {
+    /// Splits the user input into its components. The first comma-separated part is the name of the
+    /// place, optionally preceded or followed by a postal code. The remaining parts are recognized as
+    /// postal code, country code or name and state, in any order. If several parts are countries, the
+    /// last one is taken.
+    pub fn parse(input: &str) -> PlaceQuery {
+        let mut parts = input.split(',').map(str::trim).filter(|part| !part.is_empty());
+        let mut query = PlaceQuery::default();
+
+        let first = parts.next().unwrap_or_default();
+        let words: Vec<&str> = first.split_whitespace().collect();
+        query.name = match words.as_slice() {
+            [postcode, rest @ ..] if is_postcode(postcode) => {
+                query.postcode = Some(strip_postcode_prefix(postcode));
+                rest.join(" ")
+            },
+            [rest @ .., postcode] if !rest.is_empty() && is_postcode(postcode) => {
+                query.postcode = Some(strip_postcode_prefix(postcode));
+                rest.join(" ")
+            },
+            _ => first.to_string(),
+        };
+
+        let mut unknown_parts = Vec::new();
+        for part in parts {
+            if query.postcode.is_none() && is_postcode(part) {
+                query.postcode = Some(strip_postcode_prefix(part));
+            } else {
+                unknown_parts.push(part);
+            }
+        }
+
+        // the last country wins, since earlier codes are rather states, e.g. `IL` in `Springfield, IL, USA`
+        if let Some(index) = unknown_parts.iter().rposition(|part| Country::parse(part).code().is_some()) {
+            query.country = Some(Country::parse(unknown_parts.remove(index)));
+        }
+
+        // with two unknown qualifiers, the last one is most likely the country
+        match (unknown_parts.as_slice(), &query.country) {
+            ([state, .., country], None) => {
+                query.state = Some(state.to_string());
+                query.country = Some(Country::Name(country.to_string()));
+            },
+            ([state, ..], _) => query.state = Some(state.to_string()),
+            _ => (),
+        }
+
+        query
+    }
+
+    /// Replaces the country of the query by the explicitly given one, if any.
+    pub fn with_country(mut self, country: Option<&str>) -> PlaceQuery {
+        if let Some(country) = country.filter(|c| !c.trim().is_empty()) {
+            self.country = Some(Country::parse(country));
+        }
+        self
+    }
+
+    pub fn country_code(&self) -> Option<&str> {
+        self.country.as_ref().and_then(Country::code)
+    }
+
+    /// Returns the region to search in. A known country of the query takes precedence over the `default`.
+    pub fn region(&self, default: &SearchRegion) -> SearchRegion {
+        match self.country_code() {
+            Some(code) => SearchRegion::Countries(vec![code.to_string()]),
+            None => default.clone(),
+        }
+    }
+
+    /// Returns the query as free-form text for backends without structured search. Country codes are
+    /// left out, since those are covered by the [PlaceQuery::region].
+    pub fn free_form(&self) -> String {
+        match self.country {
+            Some(Country::Code(_)) => PlaceQuery { country: None, ..self.clone() }.to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+impl fmt::Display for PlaceQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self.postcode {
+            Some(postcode) => format!("{} {}", postcode, self.name).trim().to_string(),
+            None => self.name.clone(),
+        };
+
+        let parts: Vec<String> = std::iter::once(name)
+            .chain(self.state.clone())
+            .chain(self.country.as_ref().map(Country::to_string))
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+// postal codes like 8010, A-8010 or D-80331
+fn is_postcode(word: &str) -> bool {
+    let digits = strip_postcode_prefix(word);
+    digits.len() >= 3 && digits.len() <= 6 && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+fn strip_postcode_prefix(word: &str) -> String {
+    match word.split_once('-') {
+        Some((prefix, digits)) if prefix.len() <= 2 && prefix.chars().all(|c| c.is_ascii_alphabetic()) => digits,
+        _ => word,
+    }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn postcode_first() {
+        let query = PlaceQuery::parse("8010 Graz, AT");
+        assert_eq!(query.name, "Graz");
+        assert_eq!(query.postcode.as_deref(), Some("8010"));
+        assert_eq!(query.country_code(), Some("at"));
+        assert_eq!(query.state, None);
+    }
+
+    #[test]
+    fn postcode_last() {
+        let query = PlaceQuery::parse("München D-80331, Deutschland");
+        assert_eq!(query.name, "München");
+        assert_eq!(query.postcode.as_deref(), Some("80331"));
+        assert_eq!(query.country_code(), Some("de"));
+
+        let query = PlaceQuery::parse("Graz, 8010");
+        assert_eq!(query.name, "Graz");
+        assert_eq!(query.postcode.as_deref(), Some("8010"));
+    }
+
+    #[test]
+    fn abbreviated_states_are_no_countries() {
+        let query = PlaceQuery::parse("Neustadt, NÖ");
+        assert_eq!(query.state.as_deref(), Some("NÖ"));
+        assert_eq!(query.country, None);
+
+        let query = PlaceQuery::parse("Linz, OÖ, Österreich");
+        assert_eq!(query.state.as_deref(), Some("OÖ"));
+        assert_eq!(query.country_code(), Some("at"));
+    }
+
+    #[test]
+    fn state_code_and_country() {
+        for (input, state) in [("Springfield, IL, USA", "IL"), ("Portland, ME, USA", "ME"), ("Boulder, CO, US", "CO")] {
+            let query = PlaceQuery::parse(input);
+            assert_eq!(query.state.as_deref(), Some(state), "state of {}", input);
+            assert_eq!(query.country_code(), Some("us"), "country of {}", input);
+        }
+    }
+
+    #[test]
+    fn unknown_qualifiers() {
+        let query = PlaceQuery::parse("Springfield, Illinois, Vereinigte Staaten");
+        assert_eq!(query.state.as_deref(), Some("Illinois"));
+        assert_eq!(query.country, Some(Country::Name("Vereinigte Staaten".to_string())));
+
+        let query = PlaceQuery::parse("Graz, Steiermark");
+        assert_eq!(query.state.as_deref(), Some("Steiermark"));
+        assert_eq!(query.country, None);
+    }
+}
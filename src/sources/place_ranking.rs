@@ -0,0 +1,157 @@
+use super::nominatim::{Place, SearchRegion};
+use super::place_query::PlaceQuery;
+use crate::utils::text;
+
+// weights of the individual signals, which are each scored between 0 and 1
+const NAME_WEIGHT: f32 = 3.0;
+const PREVIOUS_CHOICE_WEIGHT: f32 = 2.0;
+const IMPORTANCE_WEIGHT: f32 = 1.5;
+const REGION_WEIGHT: f32 = 1.0;
+const POPULATION_WEIGHT: f32 = 1.0;
+const RANK_WEIGHT: f32 = 0.5;
+
+/// Minimum score difference between the best and the second best place for the best one to be
+/// considered a clear winner.
+const AMBIGUITY_MARGIN: f32 = 1.5;
+// the population of a place with a population score of 1, i.e. a city with 10 million inhabitants
+const MAX_POPULATION_EXPONENT: f32 = 7.0;
+const LOWEST_PLACE_RANK: f32 = 30.0;
+
+/// Everything besides the places themselves that indicates which place the user is looking for.
+pub struct RankingContext<'a> {
+    pub search_term: &'a str,
+    pub region: &'a SearchRegion,
+    /// references of the places the user has previously picked
+    pub previous_choices: &'a [String],
+}
+
+pub struct Ranking<'a> {
+    /// the places ordered from most to least likely, each with its score
+    pub places: Vec<(f32, &'a Place)>,
+}
+impl<'a> Ranking<'a> {
+    /// Returns the best place if it is clearly more likely than all others, `None` if the user should decide.
+    pub fn clear_winner(&self) -> Option<&'a Place> {
+        match self.places.as_slice() {
+            [(_, only)] => Some(only),
+            [(best_score, best), (second_score, _), ..] if best_score - second_score >= AMBIGUITY_MARGIN => Some(best),
+            _ => None,
+        }
+    }
+
+    pub fn ordered(&self) -> Vec<&'a Place> {
+        self.places.iter().map(|(_, place)| *place).collect()
+    }
+}
+
+pub fn rank<'a>(places: &'a [Place], context: &RankingContext) -> Ranking<'a> {
+    // only the name is compared, qualifiers like the postal code or state are not part of a place's name
+    let search_name = text::normalize(&PlaceQuery::parse(context.search_term).name);
+
+    let mut scored: Vec<(f32, &Place)> = places.iter()
+        .map(|place| (score(place, &search_name, context), place))
+        .collect();
+
+    scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    Ranking { places: scored }
+}
+
+fn score(place: &Place, search_name: &str, context: &RankingContext) -> f32 {
+    let previous_choice = place.reference()
+        .is_some_and(|reference| context.previous_choices.contains(&reference));
+    let region = context.region != &SearchRegion::None && context.region.contains(place);
+    let population = place.population()
+        .map(|p| ((p.max(1) as f32).log10() / MAX_POPULATION_EXPONENT).min(1.0))
+        .unwrap_or(0.0);
+    let rank = (LOWEST_PLACE_RANK - place.place_rank as f32).clamp(0.0, LOWEST_PLACE_RANK) / LOWEST_PLACE_RANK;
+
+    NAME_WEIGHT * name_score(place, search_name)
+        + PREVIOUS_CHOICE_WEIGHT * f32::from(u8::from(previous_choice))
+        + IMPORTANCE_WEIGHT * place.importance.clamp(0.0, 1.0)
+        + REGION_WEIGHT * f32::from(u8::from(region))
+        + POPULATION_WEIGHT * population
+        + RANK_WEIGHT * rank
+}
+
+// 1 if any name of the place matches the search exactly, 0.5 if one is the beginning of the other
+fn name_score(place: &Place, search_name: &str) -> f32 {
+    place.names()
+        .map(|name| {
+            let name = text::normalize(name);
+            if name == search_name {
+                1.0
+            } else if name.starts_with(search_name) || search_name.starts_with(&name) {
+                0.5
+            } else {
+                0.0
+            }
+        })
+        .fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::common::Coordinates;
+    use crate::sources::nominatim::{Address, AddressLevel, Extratags};
+
+    fn place(id: i64, name: &str, country_code: &str, population: u64, importance: f32) -> Place {
+        let mut address = Address::default().with_level(&AddressLevel::Municipality, Some(name.to_string()));
+        address.country_code = Some(country_code.to_string());
+
+        let mut place = Place::new(id, &Coordinates::new(47.0, 15.0), name, AddressLevel::Municipality, address);
+        place.osm_type = Some("relation".to_string());
+        place.osm_id = Some(id);
+        place.importance = importance;
+        place.extratags = Some(Extratags { population: Some(population.to_string()), ..Default::default() });
+        place
+    }
+
+    fn rank_ids(places: &[Place], search_term: &str, region: &SearchRegion, previous_choices: &[String])
+        -> (Vec<i64>, Option<i64>)
+    {
+        let ranking = rank(places, &RankingContext { search_term, region, previous_choices });
+        let ids = ranking.ordered().iter().map(|place| place.id).collect();
+        (ids, ranking.clear_winner().map(|place| place.id))
+    }
+
+    #[test]
+    fn single_place_wins() {
+        let places = [place(1, "Graz", "at", 300_000, 0.6)];
+        assert_eq!(rank_ids(&places, "Gra", &SearchRegion::None, &[]), (vec![1], Some(1)));
+    }
+
+    #[test]
+    fn exact_and_important_match_wins() {
+        let places = [place(1, "Grazer Feld", "at", 100, 0.1), place(2, "Graz", "at", 300_000, 0.6)];
+        assert_eq!(rank_ids(&places, "Graz", &SearchRegion::None, &[]), (vec![2, 1], Some(2)));
+    }
+
+    #[test]
+    fn qualifiers_are_not_part_of_the_name() {
+        let places = [place(1, "Grazer Feld", "at", 100, 0.1), place(2, "Graz", "at", 300_000, 0.6)];
+        assert_eq!(rank_ids(&places, "8010 Graz, AT", &SearchRegion::None, &[]).1, Some(2));
+    }
+
+    #[test]
+    fn similar_places_are_ambiguous() {
+        let places = [place(1, "Springfield", "us", 110_000, 0.5), place(2, "Springfield", "us", 170_000, 0.5)];
+        assert_eq!(rank_ids(&places, "Springfield", &SearchRegion::None, &[]), (vec![2, 1], None));
+    }
+
+    #[test]
+    fn previous_choice_decides() {
+        let places = [place(1, "Springfield", "us", 110_000, 0.5), place(2, "Springfield", "us", 170_000, 0.5)];
+        let previous_choices = ["R1".to_string()];
+        assert_eq!(rank_ids(&places, "Springfield", &SearchRegion::None, &previous_choices), (vec![1, 2], Some(1)));
+    }
+
+    #[test]
+    fn region_is_preferred() {
+        let places = [place(1, "Vienna", "us", 16_000, 0.4), place(2, "Vienna", "at", 1_900_000, 0.4)];
+        let region = SearchRegion::Countries(vec!["us".to_string()]);
+        // the region outweighs the population, but not by enough to decide without asking
+        assert_eq!(rank_ids(&places, "Vienna", &region, &[]), (vec![1, 2], None));
+    }
+}
@@ -0,0 +1,170 @@
+use super::common::ApiError;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+/// Identifies on whose behalf a request is made, e.g. the ID of the invoking Discord user.
+pub type Requester = u64;
+
+/// Limits requests to an API to one per `interval`. Requests that have to wait are queued per
+/// requester and served round-robin, so a single user cannot starve everyone else.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    capacity: usize,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    // waiting requests of every requester, with the requester to be served next in front
+    queues: VecDeque<(Requester, VecDeque<oneshot::Sender<()>>)>,
+    queued: usize,
+    next_slot: Instant,
+    dispatching: bool,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration, capacity: usize) -> RateLimiter {
+        RateLimiter {
+            interval,
+            capacity,
+            state: Mutex::new(LimiterState {
+                queues: VecDeque::new(),
+                queued: 0,
+                next_slot: Instant::now(),
+                dispatching: false,
+            }),
+        }
+    }
+
+    /// Waits until the `requester` may send the next request.
+    /// # Errors
+    /// [ApiError::Busy] if too many requests are already waiting.
+    pub async fn acquire(&'static self, requester: Requester) -> Result<(), ApiError> {
+        let receiver = {
+            let mut state = self.lock();
+            let now = Instant::now();
+
+            // pass right through if nobody is waiting and the last request was long enough ago
+            if state.queued == 0 && state.next_slot <= now {
+                state.next_slot = now + self.interval;
+                return Ok(());
+            }
+
+            if state.queued >= self.capacity {
+                return Err(ApiError::Busy);
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            match state.queues.iter_mut().find(|(r, _)| *r == requester) {
+                Some((_, queue)) => queue.push_back(sender),
+                None => state.queues.push_back((requester, VecDeque::from([sender]))),
+            }
+            state.queued += 1;
+
+            if !state.dispatching {
+                state.dispatching = true;
+                tokio::spawn(self.dispatch());
+            }
+
+            receiver
+        };
+
+        // the sender is only dropped without a signal if the dispatcher itself died
+        receiver.await.map_err(|_| ApiError::Busy)
+    }
+
+    async fn dispatch(&'static self) {
+        loop {
+            let next_slot = self.lock().next_slot;
+            tokio::time::sleep_until(next_slot).await;
+
+            let mut state = self.lock();
+            while let Some((requester, mut queue)) = state.queues.pop_front() {
+                let Some(sender) = queue.pop_front() else {
+                    continue;
+                };
+                state.queued -= 1;
+
+                if !queue.is_empty() {
+                    state.queues.push_back((requester, queue)); // requester has to line up again
+                }
+
+                // skip requests that have been cancelled while waiting, without using up the slot
+                if sender.send(()).is_ok() {
+                    state.next_slot = Instant::now() + self.interval;
+                    break;
+                }
+            }
+
+            if state.queued == 0 {
+                state.dispatching = false;
+                return;
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn limiter(interval: Duration, capacity: usize) -> &'static RateLimiter {
+        Box::leak(Box::new(RateLimiter::new(interval, capacity)))
+    }
+
+    #[tokio::test]
+    async fn spaces_requests_by_interval() {
+        let limiter = limiter(Duration::from_millis(50), 20);
+        let start = Instant::now();
+        limiter.acquire(1).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(50));
+        limiter.acquire(1).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn serves_requesters_in_turn() {
+        let limiter = limiter(Duration::from_millis(20), 20);
+        limiter.acquire(1).await.unwrap();
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        for (index, requester) in [1, 1, 1, 2].into_iter().enumerate() {
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                limiter.acquire(requester).await.unwrap();
+                sender.send(index).unwrap();
+            });
+            tokio::task::yield_now().await; // let the request line up before the next one
+        }
+        drop(sender);
+
+        let mut order = Vec::new();
+        while let Some(index) = receiver.recv().await {
+            order.push(index);
+        }
+        // the second requester doesn't have to wait for all requests of the first one
+        assert_eq!(order, vec![0, 3, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_beyond_capacity() {
+        let limiter = limiter(Duration::from_secs(3600), 20);
+        limiter.acquire(1).await.unwrap();
+
+        for requester in 0..20 {
+            tokio::spawn(limiter.acquire(requester));
+            tokio::task::yield_now().await;
+        }
+        assert!(matches!(limiter.acquire(1).await, Err(ApiError::Busy)));
+        assert!(matches!(limiter.acquire(21).await, Err(ApiError::Busy)));
+    }
+}
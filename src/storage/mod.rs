@@ -0,0 +1,117 @@
+pub mod settings;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use thiserror::Error;
+
+const DATA_DIR_ENV: &str = "DATA_DIRECTORY";
+const DEFAULT_DATA_DIR: &str = "./storage";
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Failed to access storage file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to (de)serialize stored data: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// A key-value map that is persisted as a JSON file in the data directory, so its entries survive
+/// restarts of the bot. Every modification is written to disk immediately.
+#[derive(Debug)]
+pub struct PersistentMap<K, V> {
+    path: PathBuf,
+    entries: RwLock<HashMap<K, V>>,
+}
+impl<K, V> PersistentMap<K, V>
+where
+    K: Eq + Hash + Clone + Serialize + DeserializeOwned,
+    V: Clone + Serialize + DeserializeOwned,
+{
+    /// Loads the map stored under the given `name`, or creates an empty one if it does not exist yet.
+    pub fn load(name: &str) -> Result<Self, StorageError> {
+        let directory = std::env::var(DATA_DIR_ENV).unwrap_or_else(|_| DEFAULT_DATA_DIR.to_string());
+        let path = PathBuf::from(directory).join(format!("{}.json", name));
+
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(PersistentMap { path, entries: RwLock::new(entries) })
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.read().get(key).cloned()
+    }
+
+    /// Reads a part of the entry of the given `key` without cloning the whole entry.
+    pub fn get_with<R>(&self, key: &K, read: impl FnOnce(&V) -> R) -> Option<R> {
+        self.read().get(key).map(read)
+    }
+
+    pub fn entries(&self) -> Vec<(K, V)> {
+        self.read().iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Result<Option<V>, StorageError> {
+        let mut entries = self.write();
+        let previous = entries.insert(key, value);
+        self.persist(&entries)?;
+
+        Ok(previous)
+    }
+
+    pub fn remove(&self, key: &K) -> Result<Option<V>, StorageError> {
+        let mut entries = self.write();
+        let removed = entries.remove(key);
+        if removed.is_some() {
+            self.persist(&entries)?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Modifies the entry of the given `key` in place, starting from its default value if there is no
+    /// such entry yet, and returns whatever the `modify` function returns.
+    pub fn update<F, R>(&self, key: K, modify: F) -> Result<R, StorageError>
+    where
+        V: Default,
+        F: FnOnce(&mut V) -> R,
+    {
+        let mut entries = self.write();
+        let result = modify(entries.entry(key).or_default());
+        self.persist(&entries)?;
+
+        Ok(result)
+    }
+
+    fn persist(&self, entries: &HashMap<K, V>) -> Result<(), StorageError> {
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        // write to a temporary file first, so a crash never leaves behind a half-written file
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(entries)?)?;
+        std::fs::rename(&temp_path, &self.path)?;
+
+        Ok(())
+    }
+
+    // a poisoned lock only means that another thread panicked while holding it, the map itself stays usable
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<K, V>> {
+        self.entries.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<K, V>> {
+        self.entries.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::sources::nominatim::{Place, SearchRegion};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Settings that an administrator can configure for their whole guild.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct GuildSettings {
+    pub search_region: SearchRegion,
+    /// the language used for members who neither chose one nor have a Discord locale
+    pub language: Option<String>,
+}
+
+/// Settings and data that belong to a single user.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UserSettings {
+    /// places saved under an alias, keyed by the normalized alias
+    pub saved_places: BTreeMap<String, SavedPlace>,
+    /// references of the places the user picked from selection menus, most recent first
+    pub recent_places: Vec<String>,
+    /// reference of the place the user picked for each normalized search term, limited to the
+    /// places in `recent_places`
+    pub place_choices: BTreeMap<String, String>,
+    /// the language the user chose to be talked to in, regardless of their Discord locale
+    pub language: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedPlace {
+    /// the alias as the user typed it
+    pub alias: String,
+    /// the fully resolved place, so it can be used without geocoding it again
+    pub place: Place,
+}
+
+/// A message showing the current time at several places, which the bot keeps up to date.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WorldClock {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    /// the fully resolved places in the order they are shown
+    pub places: Vec<Place>,
+    /// the language of the user who posted the clock, which it is shown in
+    pub language: String,
+}
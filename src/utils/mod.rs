@@ -1 +1,2 @@
-pub mod parsing;
\ No newline at end of file
+pub mod parsing;
+pub mod text;
\ No newline at end of file
@@ -1,40 +1,387 @@
-use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use crate::sources::common::Coordinates;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error("Failed to parse temporal datatype: {0}")]
     ChronoFormat(#[from] chrono::ParseError),
+    #[error("'{0}' does not match any known date or time format")]
+    UnknownFormat(String),
 }
 
+// tried one after another, so more specific formats have to come first
+const DATE_FORMATS: [&str; 9] = [
+    "%Y-%m-%d", // ISO 8601
+    // two-digit years first, since %Y would read `24` as the year 24
+    "%d.%m.%y", "%d.%m.%Y", // German
+    "%d/%m/%y", "%d/%m/%Y", // British
+    "%d %B %Y", "%d. %B %Y", "%B %d %Y", "%B %d, %Y",
+];
+// day and month only, the year is filled in with the current one
+const PARTIAL_DATE_FORMATS: [&str; 5] = ["%d.%m.", "%d.%m", "%d/%m", "%d %B", "%d. %B"];
+// chrono only knows English month names, so German ones are translated before parsing
+const GERMAN_MONTHS: [(&str, &str); 13] = [
+    ("januar", "january"), ("jänner", "january"), ("februar", "february"), ("märz", "march"),
+    ("mai", "may"), ("juni", "june"), ("juli", "july"), ("oktober", "october"), ("dezember", "december"),
+    ("jän", "jan"), ("mär", "mar"), ("okt", "oct"), ("dez", "dec"),
+];
+const TIME_FORMATS: [&str; 5] = ["%H:%M", "%H:%M:%S", "%H.%M", "%I:%M %p", "%I:%M%p"];
+const RELATIVE_DAYS: [(&str, i64); 6] = [
+    ("today", 0), ("heute", 0),
+    ("yesterday", -1), ("gestern", -1),
+    ("tomorrow", 1), ("morgen", 1),
+];
+const WEEKDAYS: [(&str, Weekday); 14] = [
+    ("monday", Weekday::Mon), ("montag", Weekday::Mon),
+    ("tuesday", Weekday::Tue), ("dienstag", Weekday::Tue),
+    ("wednesday", Weekday::Wed), ("mittwoch", Weekday::Wed),
+    ("thursday", Weekday::Thu), ("donnerstag", Weekday::Thu),
+    ("friday", Weekday::Fri), ("freitag", Weekday::Fri),
+    ("saturday", Weekday::Sat), ("samstag", Weekday::Sat),
+    ("sunday", Weekday::Sun), ("sonntag", Weekday::Sun),
+];
+
 pub fn parse_datetime(date_input: Option<String>, time_input: Option<String>) -> Result<NaiveDateTime, ParseError> {
     // default to today if no date is provided
     let date_value: NaiveDate = match date_input {
-        Some(date_str) => try_parse_localized_date(&date_str),
+        Some(date_str) => parse_date(&date_str, Local::now().date_naive())?,
         None => Local::now().naive_local().date(),
     };
 
     // default to current time if no date is provided
     let time_value: NaiveTime = match time_input {
-        Some(time_str) => try_parse_localized_time(&time_str),
+        Some(time_str) => parse_time(&time_str)?,
         None => Local::now().time(),
     };
 
     Ok(date_value.and_time(time_value))
 }
 
-fn try_parse_localized_date(date_str: &str) -> NaiveDate {
-    // TODO Get common localized formats for dates
+/// Parses a date in one of the common English or German formats, e.g. `2024-03-31`, `31.03.2024`,
+/// `31/03/24`, `31 March 2024`, `31. März 2024` or `31.03.`. Relative days like `tomorrow` refer to
+/// `today`, weekdays like `Friday` to their next occurrence, which is `today` itself on that weekday.
+pub fn parse_date(date_str: &str, today: NaiveDate) -> Result<NaiveDate, ParseError> {
+    let input = date_str.trim();
+    let lowercase = input.to_lowercase();
+
+    if let Some((_, offset)) = RELATIVE_DAYS.iter().find(|(day, _)| *day == lowercase) {
+        return Ok(today + Duration::days(*offset));
+    }
+    if let Some((_, weekday)) = WEEKDAYS.iter().find(|(day, _)| *day == lowercase) {
+        let days_ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        return Ok(today + Duration::days(days_ahead.into()));
+    }
+
+    let english = &input.split_whitespace()
+        .map(|word| {
+            let lowercase = word.to_lowercase();
+            GERMAN_MONTHS.iter().find(|(german, _)| *german == lowercase).map_or(word, |(_, english)| english)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    DATE_FORMATS.iter()
+        .find_map(|format| NaiveDate::parse_from_str(english, format).ok())
+        .or_else(|| {
+            // chrono cannot parse a date without year, so append the current one
+            let with_year = format!("{} {}", english, today.year());
+            PARTIAL_DATE_FORMATS.iter()
+                .find_map(|format| NaiveDate::parse_from_str(&with_year, &format!("{} %Y", format)).ok())
+        })
+        .ok_or_else(|| ParseError::UnknownFormat(input.to_string()))
+}
+
+/// Parses a time of day like `15:30`, `15.30`, `15:30:00`, `3:30 pm`, `3pm` or just the hour `15`.
+pub fn parse_time(time_str: &str) -> Result<NaiveTime, ParseError> {
+    let input = time_str.trim().to_uppercase();
+    // German times are often written with a trailing "Uhr" or "h"
+    let input = input.trim_end_matches("UHR").trim_end_matches('H').trim();
+
+    // chrono requires minutes, so full hours like `15` or `3 pm` are handled separately
+    let full_hour = match input.strip_suffix("AM").or(input.strip_suffix("PM")) {
+        Some(hour) => hour.trim().parse::<u32>().ok()
+            .filter(|hour| (1..=12).contains(hour))
+            .map(|hour| hour % 12 + if input.ends_with("PM") { 12 } else { 0 }),
+        None => input.parse::<u32>().ok(),
+    };
+
+    full_hour.and_then(|hour| NaiveTime::from_hms_opt(hour, 0, 0))
+        .or_else(|| TIME_FORMATS.iter().find_map(|format| NaiveTime::parse_from_str(input, format).ok()))
+        .ok_or_else(|| ParseError::UnknownFormat(time_str.trim().to_string()))
+}
+
+/// Tries to interpret the user input as a geographic position instead of a place name. Supported are
+/// - decimal degrees, e.g. `47.07, 15.44` or `47.07 15.44`
+/// - degrees, minutes and seconds, e.g. `47°04'N 15°26'E` or `N 47° 4' 12" E 15° 26'`
+/// - full Open Location Codes (Plus Codes), e.g. `8FVC9G8F+6X`
+///
+/// Returns `None` if the input is none of those, so it can be treated as a place name.
+pub fn parse_coordinates(input: &str) -> Option<Coordinates> {
+    let input = input.trim();
+
+    parse_decimal_coordinates(input)
+        .or_else(|| parse_dms_coordinates(input))
+        .or_else(|| parse_plus_code(input))
+        .filter(|c| (-90.0..=90.0).contains(&c.latitude) && (-180.0..=180.0).contains(&c.longitude))
+}
+
+fn parse_decimal_coordinates(input: &str) -> Option<Coordinates> {
+    let parts: Vec<&str> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    match parts.as_slice() {
+        [lat, lon] => Some(Coordinates::new(lat.parse().ok()?, lon.parse().ok()?)),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct DmsComponent {
+    values: Vec<f64>,
+    hemisphere: Option<char>,
+}
+impl DmsComponent {
+    fn degrees(&self) -> Option<f64> {
+        let (degrees, minutes, seconds) = match self.values.as_slice() {
+            [d] => (*d, 0.0, 0.0),
+            [d, m] => (*d, *m, 0.0),
+            [d, m, s] => (*d, *m, *s),
+            _ => return None,
+        };
+
+        if minutes >= 60.0 || seconds >= 60.0 {
+            return None;
+        }
+
+        let absolute = degrees.abs() + minutes / 60.0 + seconds / 3600.0;
+        match self.hemisphere {
+            Some('S') | Some('W') => Some(-absolute),
+            _ => Some(absolute.copysign(degrees)),
+        }
+    }
+}
+
+fn parse_dms_coordinates(input: &str) -> Option<Coordinates> {
+    let mut components: Vec<DmsComponent> = Vec::new();
+    let mut current = DmsComponent::default();
+    let mut number = String::new();
+
+    let flush_number = |number: &mut String, component: &mut DmsComponent| -> Option<()> {
+        if !number.is_empty() {
+            component.values.push(number.parse().ok()?);
+            number.clear();
+        }
+        Some(())
+    };
+
+    for c in input.chars() {
+        match c.to_ascii_uppercase() {
+            '0'..='9' | '.' | '-' => number.push(c),
+            '°' | 'º' | '\'' | '′' | '’' | '"' | '″' | '”' | ',' | ';' => flush_number(&mut number, &mut current)?,
+            c if c.is_whitespace() => flush_number(&mut number, &mut current)?,
+            hemisphere @ ('N' | 'S' | 'E' | 'W') => {
+                flush_number(&mut number, &mut current)?;
+
+                if current.values.is_empty() {
+                    // hemisphere is written in front of the degrees
+                    current.hemisphere = Some(hemisphere);
+                } else if current.hemisphere.is_none() {
+                    // hemisphere is written after the degrees and concludes this component
+                    current.hemisphere = Some(hemisphere);
+                    components.push(std::mem::take(&mut current));
+                } else {
+                    // the previous component had a leading hemisphere, this one starts a new component
+                    components.push(std::mem::take(&mut current));
+                    current.hemisphere = Some(hemisphere);
+                }
+            },
+            _ => return None, // any other character means this is not a coordinate
+        }
+    }
+    flush_number(&mut number, &mut current)?;
+    if !current.values.is_empty() {
+        components.push(current);
+    }
+
+    let [first, second] = components.as_slice() else {
+        return None;
+    };
+
+    // the components may be given in any order if their hemispheres are stated explicitly
+    let is_latitude = |component: &DmsComponent| matches!(component.hemisphere, Some('N') | Some('S'));
+    let is_longitude = |component: &DmsComponent| matches!(component.hemisphere, Some('E') | Some('W'));
+
+    let (latitude, longitude) = if is_longitude(first) || is_latitude(second) {
+        (second, first)
+    } else {
+        (first, second)
+    };
+
+    if is_longitude(latitude) || is_latitude(longitude) {
+        return None; // e.g. two latitudes
+    }
+
+    Some(Coordinates::new(latitude.degrees()?, longitude.degrees()?))
+}
+
+const PLUS_CODE_ALPHABET: &str = "23456789CFGHJMPQRVWX";
+const PLUS_CODE_SEPARATOR_POSITION: usize = 8;
+const PLUS_CODE_PAIR_RESOLUTIONS: [f64; 5] = [20.0, 1.0, 0.05, 0.0025, 0.000125];
+const PLUS_CODE_GRID_ROWS: usize = 5;
+const PLUS_CODE_GRID_COLUMNS: usize = 4;
+
+/// Decodes a full Open Location Code to the coordinates at the centre of its area.
+/// Short codes are not supported, since they require a reference location to be recovered.
+fn parse_plus_code(input: &str) -> Option<Coordinates> {
+    let code = input.to_ascii_uppercase();
+
+    if code.find('+')? != PLUS_CODE_SEPARATOR_POSITION || code.matches('+').count() != 1 {
+        return None;
+    }
+
+    // padding characters are only allowed right before the separator
+    let digits: String = code.chars().filter(|&c| c != '+').collect();
+    let unpadded = digits.trim_end_matches('0');
+    if unpadded.contains('0') || unpadded.len() < 2 || (unpadded.len() % 2 != 0 && unpadded.len() < 10) {
+        return None;
+    }
+
+    let indices: Vec<usize> = unpadded.chars()
+        .map(|c| PLUS_CODE_ALPHABET.find(c))
+        .collect::<Option<_>>()?;
 
-    // TODO Try to parse the date in one of these formats one after another
+    // the first pair only covers 9 x 18 cells of the world
+    if indices[0] >= 9 || indices[1] >= 18 {
+        return None;
+    }
 
-    NaiveDate::from_ymd(2023, 1, 1)
+    let mut latitude = -90.0;
+    let mut longitude = -180.0;
+    let mut latitude_resolution = PLUS_CODE_PAIR_RESOLUTIONS[0];
+    let mut longitude_resolution = PLUS_CODE_PAIR_RESOLUTIONS[0];
+
+    for (pair, digits) in indices.iter().take(10).collect::<Vec<_>>().chunks(2).enumerate() {
+        latitude_resolution = PLUS_CODE_PAIR_RESOLUTIONS[pair];
+        longitude_resolution = PLUS_CODE_PAIR_RESOLUTIONS[pair];
+        latitude += *digits[0] as f64 * latitude_resolution;
+        longitude += *digits[1] as f64 * longitude_resolution;
+    }
+
+    // any digits beyond the tenth refine the area in a grid of 5 rows and 4 columns each
+    for &digit in indices.iter().skip(10) {
+        latitude_resolution /= PLUS_CODE_GRID_ROWS as f64;
+        longitude_resolution /= PLUS_CODE_GRID_COLUMNS as f64;
+        latitude += (digit / PLUS_CODE_GRID_COLUMNS) as f64 * latitude_resolution;
+        longitude += (digit % PLUS_CODE_GRID_COLUMNS) as f64 * longitude_resolution;
+    }
+
+    Some(Coordinates::new(latitude + latitude_resolution / 2.0, longitude + longitude_resolution / 2.0))
 }
 
-fn try_parse_localized_time(date_str: &str) -> NaiveTime {
-    // TODO Get common localized, accepted formats for time
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn assert_coordinates(input: &str, latitude: f64, longitude: f64) {
+        let coordinates = parse_coordinates(input).unwrap_or_else(|| panic!("failed to parse {}", input));
+        assert!((coordinates.latitude - latitude).abs() < 1e-5, "latitude of {}: {}", input, coordinates.latitude);
+        assert!((coordinates.longitude - longitude).abs() < 1e-5, "longitude of {}: {}", input, coordinates.longitude);
+    }
+
+    #[test]
+    fn dates() {
+        let today = date(2024, 3, 13); // a Wednesday
+        assert_eq!(parse_date("2024-03-31", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("31.03.2024", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("31.03.24", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("31/03/2024", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("31 March 2024", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("March 31, 2024", today).unwrap(), date(2024, 3, 31));
+        assert!(parse_date("31.13.2024", today).is_err());
+        assert!(parse_date("someday", today).is_err());
+    }
 
-    // TODO Try to parse the time in one of these formats one after another
+    #[test]
+    fn german_month_names() {
+        let today = date(2024, 3, 13);
+        assert_eq!(parse_date("31. März 2024", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("1 Jänner 2025", today).unwrap(), date(2025, 1, 1));
+        assert_eq!(parse_date("3 Mai", today).unwrap(), date(2024, 5, 3));
+        assert_eq!(parse_date("24. Dezember", today).unwrap(), date(2024, 12, 24));
+    }
 
-    todo!("Parse time")
-}
\ No newline at end of file
+    #[test]
+    fn dates_without_year() {
+        let today = date(2024, 3, 13);
+        assert_eq!(parse_date("31.03.", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("31/03", today).unwrap(), date(2024, 3, 31));
+        assert_eq!(parse_date("31 March", today).unwrap(), date(2024, 3, 31));
+    }
+
+    #[test]
+    fn relative_dates() {
+        let today = date(2024, 3, 13);
+        assert_eq!(parse_date("today", today).unwrap(), today);
+        assert_eq!(parse_date("Morgen", today).unwrap(), date(2024, 3, 14));
+        assert_eq!(parse_date("yesterday", today).unwrap(), date(2024, 3, 12));
+        assert_eq!(parse_date("Friday", today).unwrap(), date(2024, 3, 15));
+        assert_eq!(parse_date("montag", today).unwrap(), date(2024, 3, 18));
+        assert_eq!(parse_date("wednesday", today).unwrap(), today);
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parse_time("15:30").unwrap(), time(15, 30));
+        assert_eq!(parse_time("15.30").unwrap(), time(15, 30));
+        assert_eq!(parse_time("15:30:45").unwrap(), NaiveTime::from_hms_opt(15, 30, 45).unwrap());
+        assert_eq!(parse_time("15").unwrap(), time(15, 0));
+        assert_eq!(parse_time("15 Uhr").unwrap(), time(15, 0));
+        assert_eq!(parse_time("15:30h").unwrap(), time(15, 30));
+        assert_eq!(parse_time("3:30 pm").unwrap(), time(15, 30));
+        assert_eq!(parse_time("3pm").unwrap(), time(15, 0));
+        assert_eq!(parse_time("12 am").unwrap(), time(0, 0));
+        assert_eq!(parse_time("12 PM").unwrap(), time(12, 0));
+        assert!(parse_time("25:00").is_err());
+        assert!(parse_time("13 pm").is_err());
+    }
+
+    #[test]
+    fn decimal_coordinates() {
+        assert_coordinates("47.07, 15.44", 47.07, 15.44);
+        assert_coordinates("-33.86 151.21", -33.86, 151.21);
+        assert!(parse_coordinates("91.0, 15.44").is_none());
+        assert!(parse_coordinates("Graz").is_none());
+    }
+
+    #[test]
+    fn dms_coordinates() {
+        assert_coordinates("47°04'N 15°26'E", 47.0 + 4.0 / 60.0, 15.0 + 26.0 / 60.0);
+        assert_coordinates("N 47° 4' 12\" E 15° 26'", 47.0 + 4.0 / 60.0 + 12.0 / 3600.0, 15.0 + 26.0 / 60.0);
+        assert_coordinates("15°26'E 47°04'N", 47.0 + 4.0 / 60.0, 15.0 + 26.0 / 60.0);
+        assert_coordinates("33°52'S 151°12'E", -(33.0 + 52.0 / 60.0), 151.2);
+        assert!(parse_coordinates("47°04'N 15°26'N").is_none());
+        assert!(parse_coordinates("47°61'N 15°26'E").is_none());
+    }
+
+    #[test]
+    fn plus_codes() {
+        // centre of the example area of the Open Location Code specification in Zurich
+        assert_coordinates("8FVC9G8F+6X", 47.3655625, 8.5249375);
+        assert_coordinates("8fvc9g8f+6xq", 47.3655875, 8.524984375);
+        assert_coordinates("8FVC0000+", 47.5, 8.5);
+        assert!(parse_coordinates("9G8F+6X").is_none());
+        assert!(parse_coordinates("8FVC9G8F+6").is_none());
+        assert!(parse_coordinates("XFVC9G8F+6X").is_none());
+    }
+}
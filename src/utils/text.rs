@@ -0,0 +1,54 @@
+/// Normalizes a name for comparisons, i.e. lowercases it, removes diacritics of common Latin
+/// characters and collapses punctuation and whitespace, so that `St. Pölten` equals `st polten`.
+pub fn normalize(s: &str) -> String {
+    let mut normalized = String::with_capacity(s.len());
+
+    for c in s.chars().flat_map(char::to_lowercase) {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' | 'ă' => normalized.push('a'),
+            'ç' | 'ć' | 'č' => normalized.push('c'),
+            'ď' | 'đ' => normalized.push('d'),
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => normalized.push('e'),
+            'ì' | 'í' | 'î' | 'ï' | 'ī' => normalized.push('i'),
+            'ł' | 'ľ' | 'ĺ' => normalized.push('l'),
+            'ñ' | 'ń' | 'ň' => normalized.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => normalized.push('o'),
+            'ř' | 'ŕ' => normalized.push('r'),
+            'ś' | 'š' | 'ş' | 'ș' => normalized.push('s'),
+            'ß' => normalized.push_str("ss"),
+            'ť' | 'ţ' | 'ț' => normalized.push('t'),
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => normalized.push('u'),
+            'ý' | 'ÿ' => normalized.push('y'),
+            'ź' | 'ż' | 'ž' => normalized.push('z'),
+            c if c.is_alphanumeric() => normalized.push(c),
+            // treat punctuation like whitespace, but never add two spaces in a row
+            _ => if !normalized.is_empty() && !normalized.ends_with(' ') {
+                normalized.push(' ');
+            },
+        }
+    }
+
+    normalized.trim_end().to_string()
+}
+
+/// Computes the Levenshtein distance between two strings, i.e. the number of inserted, removed or
+/// replaced characters needed to turn one into the other.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, char_a) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1; b.len() + 1];
+
+        for (j, char_b) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(char_a != *char_b);
+            current_row[j + 1] = substitution
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[b.len()]
+}